@@ -0,0 +1,45 @@
+//! Compares the binary heap and the timer wheel as task queues.
+//!
+//! Run with `cargo bench`. Every task runs ten times on a virtual clock, so
+//! the measured time is spent almost entirely in the queue.
+
+use std::time::{Duration, Instant};
+
+use casched::{MockClock, Schedule, Scheduler, Task};
+
+const RUNS: usize = 10;
+
+fn tasks(count: usize) -> Vec<Task<'static>> {
+    (0..count)
+        .map(|i| {
+            let interval = Duration::from_millis(1 + (i as u64 * 7919) % 1000);
+            Schedule::Counted {
+                interval,
+                count: RUNS,
+            }
+            .with(|| {})
+        })
+        .collect()
+}
+
+fn bench(count: usize, wheel: bool) -> Duration {
+    let mut scheduler = Scheduler::with_clock(MockClock::new(), tasks(count));
+
+    if wheel {
+        scheduler = scheduler.with_timer_wheel(Duration::from_millis(1));
+    }
+
+    let start = Instant::now();
+    scheduler.run();
+    start.elapsed()
+}
+
+fn main() {
+    println!("{:>8} {:>12} {:>12}", "tasks", "heap", "wheel");
+
+    for count in [1_000, 10_000, 100_000] {
+        let heap = bench(count, false);
+        let wheel = bench(count, true);
+        println!("{count:>8} {heap:>12.2?} {wheel:>12.2?}");
+    }
+}
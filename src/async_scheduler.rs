@@ -0,0 +1,380 @@
+use std::{
+    any::Any,
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
+
+use crate::{Clock, Job, Next, Schedule, Scheduler, SystemClock, Task, TaskId};
+
+/// The function of an asynchronous task, returning the future to run.
+pub type AsyncTaskFunction =
+    Box<dyn FnMut() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + 'static>;
+
+impl Schedule {
+    /// Creates a task whose executions run the future returned by `f`.
+    ///
+    /// An [`AsyncScheduler`] awaits each future before rescheduling the task.
+    /// A plain [`Scheduler`] spawns the future onto the tokio runtime it runs
+    /// on without awaiting it. Outside of a runtime, e.g. on the worker
+    /// threads of [`Scheduler::with_workers`], the execution fails with an
+    /// error instead, see [`Scheduler::with_error_handler`].
+    pub fn with_async<'a, F, Fut>(self, mut f: F) -> Task<'a>
+    where
+        F: 'static + Send + FnMut() -> Fut,
+        Fut: 'static + Send + Future<Output = ()>,
+    {
+        self.with_async_boxed(Box::new(move || Box::pin(f())))
+    }
+
+    pub fn with_async_boxed<'a>(self, f: AsyncTaskFunction) -> Task<'a> {
+        Task::new(self, Job::Async(f))
+    }
+}
+
+enum Command {
+    Add(TaskId, Task<'static>),
+    Cancel(TaskId, oneshot::Sender<bool>),
+    Shutdown,
+}
+
+/// A scheduler running on a tokio runtime.
+///
+/// Waiting for the next task yields to the runtime instead of blocking the
+/// thread, see [`Clock::sleep_async`]. Synchronous tasks are still executed
+/// inline.
+pub struct AsyncScheduler<C: Clock = SystemClock> {
+    inner: Scheduler<'static, C>,
+    next_id: Arc<AtomicU64>,
+    commands: UnboundedReceiver<Command>,
+    sender: Option<UnboundedSender<Command>>,
+}
+
+/// Controls a running [`AsyncScheduler`], see [`AsyncScheduler::handle`].
+#[derive(Clone)]
+pub struct AsyncSchedulerHandle {
+    next_id: Arc<AtomicU64>,
+    commands: UnboundedSender<Command>,
+}
+
+impl AsyncScheduler {
+    pub fn new() -> Self {
+        Self::with_tasks(Vec::new())
+    }
+
+    pub fn with_tasks(tasks: Vec<Task<'static>>) -> Self {
+        Scheduler::with_tasks(tasks).into()
+    }
+}
+
+impl<C: Clock> AsyncScheduler<C> {
+    pub fn with_clock(clock: C, tasks: Vec<Task<'static>>) -> Self {
+        Scheduler::with_clock(clock, tasks).into()
+    }
+
+    /// Schedules an additional task relative to now.
+    pub fn add(&mut self, task: Task<'static>) -> TaskId {
+        let id = TaskId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (now, system_now) = (self.inner.clock.now(), self.inner.clock.system_now());
+        self.inner.insert_with_id(id, task, now, system_now);
+        id
+    }
+
+    /// Schedules `f` to run once after `delay` and returns a future
+    /// resolving to its return value.
+    ///
+    /// The future resolves to `None` if the task never completes, i.e. it was
+    /// cancelled, panicked or the scheduler stopped before it fired.
+    pub fn add_oneshot<F, T>(&mut self, delay: Duration, f: F) -> impl Future<Output = Option<T>>
+    where
+        F: 'static + Send + FnOnce() -> T,
+        T: 'static + Send,
+    {
+        let (sender, receiver) = oneshot::channel();
+
+        self.add(Schedule::Once(Some(delay)).with_once(move || {
+            let _ = sender.send(f());
+        }));
+
+        async move { receiver.await.ok() }
+    }
+
+    /// Cancels a task, see [`Scheduler::cancel`].
+    pub fn cancel(&mut self, id: TaskId) -> bool {
+        self.inner.cancel(id)
+    }
+
+    /// Creates a handle to control the scheduler once it runs.
+    ///
+    /// While handles exist the scheduler waits for new tasks instead of
+    /// returning once no tasks are left.
+    pub fn handle(&self) -> AsyncSchedulerHandle {
+        AsyncSchedulerHandle {
+            next_id: Arc::clone(&self.next_id),
+            commands: self.sender.clone().expect("Sender is only taken by run"),
+        }
+    }
+
+    /// Runs the scheduler until no tasks are left and all handles were
+    /// dropped, or a handle shut it down.
+    pub async fn run(mut self) {
+        // Only handles keep the channel open from here on.
+        self.sender.take();
+        let mut connected = true;
+
+        loop {
+            self.inner.handle_pool_failures();
+
+            let next = if self.inner.paused_since.is_some() {
+                self.inner
+                    .until_next(self.inner.clock.now())
+                    .map(|_| self.inner.sleep_slice)
+            } else {
+                self.inner.sync_wall_clock(self.inner.clock.now());
+
+                while self.inner.until_next(self.inner.clock.now()) == Some(Duration::ZERO) {
+                    self.fire_next().await;
+                }
+
+                self.inner.until_next(self.inner.clock.now())
+            };
+
+            let command = match (next, connected) {
+                (Some(diff), true) => tokio::select! {
+                    // Commands received before the sleep leave virtual
+                    // clocks where they are.
+                    biased;
+                    command = self.commands.recv() => command,
+                    () = self.inner.clock.sleep_async(diff) => continue,
+                },
+                (None, true) => self.commands.recv().await,
+                (Some(diff), false) => {
+                    self.inner.clock.sleep_async(diff).await;
+                    continue;
+                }
+                (None, false) => return,
+            };
+
+            match command {
+                Some(Command::Add(id, task)) => {
+                    let (now, system_now) = (self.inner.clock.now(), self.inner.clock.system_now());
+                    self.inner.insert_with_id(id, task, now, system_now);
+                }
+                Some(Command::Cancel(id, reply)) => {
+                    let _ = reply.send(self.inner.cancel(id));
+                }
+                Some(Command::Shutdown) => return,
+                None => connected = false,
+            }
+        }
+    }
+
+    /// Executes the next task like [`Scheduler::run_pending`], awaiting the
+    /// futures of asynchronous tasks.
+    async fn fire_next(&mut self) {
+        let fired_at = self.inner.clock.now();
+
+        if self.inner.defer_over_limit(fired_at) {
+            return;
+        }
+
+        let (fired, next) = match self.inner.begin_next(fired_at) {
+            Next::Execute(mut task, lateness) => {
+                let started = self.inner.clock.now();
+
+                // Spawning isolates panics of the future from the scheduler.
+                let result = match &mut task.task.f {
+                    Job::Async(f) => tokio::spawn(f()).await.map(Ok).map_err(|error| {
+                        error
+                            .try_into_panic()
+                            .unwrap_or_else(|error| Box::new(error) as Box<dyn Any + Send>)
+                    }),
+                    job => panic::catch_unwind(AssertUnwindSafe(|| job.call())),
+                };
+
+                self.inner.finish_next(task, lateness, started, result)
+            }
+            Next::Done(fired, next) => (fired, next),
+        };
+
+        if let Some((task, result)) = next {
+            let now = self.inner.clock.now();
+            self.inner.push_next(task, result, fired, fired_at, now);
+        }
+    }
+}
+
+impl<C: Clock> From<Scheduler<'static, C>> for AsyncScheduler<C> {
+    /// Runs the tasks of `scheduler` asynchronously, keeping its clock and
+    /// configuration such as callbacks, rate limit and panic handler.
+    fn from(scheduler: Scheduler<'static, C>) -> Self {
+        let next_id = Arc::new(AtomicU64::new(scheduler.next_id));
+        let (sender, commands) = mpsc::unbounded_channel();
+
+        Self {
+            inner: scheduler,
+            next_id,
+            commands,
+            sender: Some(sender),
+        }
+    }
+}
+
+impl Default for AsyncScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AsyncSchedulerHandle {
+    /// Schedules an additional task relative to the time the scheduler
+    /// receives it.
+    pub fn add(&self, task: Task<'static>) -> TaskId {
+        let id = TaskId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let _ = self.commands.send(Command::Add(id, task));
+        id
+    }
+
+    /// Cancels a task, see [`Scheduler::cancel`].
+    pub async fn cancel(&self, id: TaskId) -> bool {
+        let (reply, response) = oneshot::channel();
+
+        if self.commands.send(Command::Cancel(id, reply)).is_err() {
+            return false;
+        }
+
+        response.await.unwrap_or(false)
+    }
+
+    /// Stops the scheduler after the currently executing task.
+    pub fn shutdown(&self) {
+        let _ = self.commands.send(Command::Shutdown);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{atomic::AtomicUsize, Mutex};
+
+    use super::*;
+    use crate::MockClock;
+
+    #[tokio::test]
+    async fn async_tasks_are_awaited() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let clock = MockClock::new();
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::Counted {
+                interval: Duration::from_millis(5),
+                count: 3,
+            }
+            .with_async(move || {
+                let runs = runs.clone();
+                async move {
+                    tokio::task::yield_now().await;
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+
+        let scheduler = AsyncScheduler::with_clock(clock.clone(), vec![task]);
+        let handle = scheduler.handle();
+
+        let stopper = {
+            let runs = runs.clone();
+            Schedule::Every(Duration::from_millis(5)).with(move || {
+                if runs.load(Ordering::SeqCst) == 3 {
+                    handle.shutdown();
+                }
+            })
+        };
+        scheduler.handle().add(stopper);
+
+        scheduler.run().await;
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(clock.elapsed(), Duration::from_millis(15));
+    }
+
+    #[tokio::test]
+    async fn tasks_are_skipped_while_their_condition_is_false() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let checks = Arc::new(AtomicUsize::new(0));
+
+        let task = {
+            let (runs, checks) = (runs.clone(), checks.clone());
+            Schedule::Counted {
+                interval: Duration::from_millis(5),
+                count: 3,
+            }
+            .with_async(move || {
+                let runs = runs.clone();
+                async move {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .when(move || {
+                checks.fetch_add(1, Ordering::SeqCst);
+                false
+            })
+        };
+
+        AsyncScheduler::with_clock(MockClock::new(), vec![task])
+            .run()
+            .await;
+
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        assert_eq!(checks.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn oneshots_resolve_to_their_result() {
+        let mut scheduler = AsyncScheduler::with_clock(MockClock::new(), Vec::new());
+        let answer = scheduler.add_oneshot(Duration::from_millis(5), || 42);
+        let failed = scheduler.add_oneshot(Duration::from_millis(5), || -> u8 { panic!() });
+        let dropped = scheduler.add_oneshot(Duration::from_secs(60), || ());
+
+        let handle = scheduler.handle();
+        handle.add(Schedule::Once(Some(Duration::from_millis(10))).with({
+            let handle = handle.clone();
+            move || handle.shutdown()
+        }));
+        drop(handle);
+
+        let ((), answer, failed, dropped) = tokio::join!(scheduler.run(), answer, failed, dropped);
+
+        assert_eq!(answer, Some(42));
+        assert_eq!(failed, None);
+        assert_eq!(dropped, None);
+    }
+
+    #[test]
+    fn async_tasks_fail_outside_of_a_runtime() {
+        let errors = Arc::new(Mutex::new(Vec::new()));
+
+        let mut scheduler = {
+            let errors = errors.clone();
+            Scheduler::with_clock(MockClock::new(), Vec::new()).with_error_handler(
+                move |id, error| errors.lock().unwrap().push((id, error.to_string())),
+            )
+        };
+        let id = scheduler.add(Schedule::Once(None).with_async(|| async {}));
+        scheduler.run_pending();
+
+        let errors = errors.lock().unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, id);
+    }
+}
@@ -0,0 +1,141 @@
+use std::{error::Error, fmt};
+
+use crate::{Schedule, ScheduleError, ScheduleKind, Task};
+
+/// An invalid combination of options given to a [`TaskBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskBuildError {
+    /// [`TaskBuilder::fire_immediately`] was combined with a schedule which
+    /// runs only once, so its only execution would be moved.
+    ImmediateOneShot(ScheduleKind),
+    /// The schedule failed [`Schedule::validate`].
+    InvalidSchedule(ScheduleError),
+}
+
+impl fmt::Display for TaskBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ImmediateOneShot(kind) => {
+                write!(f, "`{kind}` runs once and can not fire immediately")
+            }
+            Self::InvalidSchedule(error) => write!(f, "invalid schedule: {error}"),
+        }
+    }
+}
+
+impl Error for TaskBuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidSchedule(error) => Some(error),
+            Self::ImmediateOneShot(_) => None,
+        }
+    }
+}
+
+/// Collects the options of a [`Task`] before its function is given, e.g.
+/// `TaskBuilder::new(schedule).name("flush").fire_immediately().build(f)`.
+#[derive(Debug)]
+pub struct TaskBuilder {
+    schedule: Schedule,
+    name: Option<String>,
+    priority: i32,
+    group: Option<String>,
+    immediate: bool,
+}
+
+impl TaskBuilder {
+    pub fn new(schedule: Schedule) -> Self {
+        Self {
+            schedule,
+            name: None,
+            priority: 0,
+            group: None,
+            immediate: false,
+        }
+    }
+
+    /// See [`Task::named`].
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// See [`Task::priority`].
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// See [`Task::in_group`].
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// See [`Task::fire_immediately`], only valid for recurring schedules.
+    pub fn fire_immediately(mut self) -> Self {
+        self.immediate = true;
+        self
+    }
+
+    /// Creates the task executing `f`, unless the schedule is invalid or the
+    /// options do not fit it.
+    pub fn build<'a, F>(self, f: F) -> Result<Task<'a>, TaskBuildError>
+    where
+        F: 'a + Send + FnMut(),
+    {
+        self.schedule
+            .validate()
+            .map_err(TaskBuildError::InvalidSchedule)?;
+
+        let one_shot = matches!(
+            self.schedule,
+            Schedule::Once(_) | Schedule::OnceAt(_) | Schedule::At(_)
+        );
+
+        if self.immediate && one_shot {
+            return Err(TaskBuildError::ImmediateOneShot(self.schedule.kind()));
+        }
+
+        let mut task = self.schedule.with(f).priority(self.priority);
+
+        if let Some(name) = self.name {
+            task = task.named(name);
+        }
+
+        if let Some(group) = self.group {
+            task = task.in_group(group);
+        }
+
+        if self.immediate {
+            task = task.fire_immediately();
+        }
+
+        Ok(task)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn one_shot_tasks_can_not_fire_immediately() {
+        let builder = TaskBuilder::new(Schedule::Once(Some(Duration::from_secs(1))));
+        let error = builder.fire_immediately().build(|| {}).unwrap_err();
+
+        assert_eq!(error, TaskBuildError::ImmediateOneShot(ScheduleKind::Once));
+        assert_eq!(
+            error.to_string(),
+            "`Once` runs once and can not fire immediately"
+        );
+
+        let builder = TaskBuilder::new(Schedule::Every(Duration::ZERO));
+        assert_eq!(
+            builder.build(|| {}).unwrap_err(),
+            TaskBuildError::InvalidSchedule(ScheduleError::ZeroInterval(ScheduleKind::Every))
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A time of the day, see [`crate::Schedule::Daily`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimeOfDay {
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl TimeOfDay {
+    pub const MIDNIGHT: Self = Self {
+        hour: 0,
+        minute: 0,
+        second: 0,
+    };
+
+    /// Returns `None` unless `hour < 24`, `minute < 60` and `second < 60`.
+    pub fn new(hour: u8, minute: u8, second: u8) -> Option<Self> {
+        (hour < 24 && minute < 60 && second < 60).then_some(Self {
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    fn seconds(&self) -> u64 {
+        u64::from(self.hour) * 60 * 60 + u64::from(self.minute) * 60 + u64::from(self.second)
+    }
+}
+
+/// A day of the week, see [`crate::Schedule::Weekly`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    /// The number of days since Monday.
+    fn index(self) -> u64 {
+        self as u64
+    }
+}
+
+/// A time zone, mapping instants onto local time, see
+/// [`crate::Schedule::Daily`].
+///
+/// Zones with daylight saving time can be implemented by looking the offset
+/// up in a time zone database. Offsets must be shorter than a day and change
+/// at most once within two days.
+pub trait TimeZone: fmt::Debug + Send + Sync {
+    /// The offset of local time from UTC in effect at `at`, in seconds east
+    /// of UTC.
+    fn utc_offset(&self, at: SystemTime) -> i32;
+}
+
+/// A time zone whose offset from UTC never changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedOffset {
+    seconds: i32,
+}
+
+impl FixedOffset {
+    pub const UTC: Self = Self { seconds: 0 };
+
+    /// Returns `None` unless the offset is shorter than a day.
+    pub fn east(seconds: i32) -> Option<Self> {
+        (seconds.unsigned_abs() < SECONDS_PER_DAY as u32).then_some(Self { seconds })
+    }
+
+    /// The offset in seconds east of UTC.
+    pub fn seconds(&self) -> i32 {
+        self.seconds
+    }
+}
+
+impl TimeZone for FixedOffset {
+    fn utc_offset(&self, _: SystemTime) -> i32 {
+        self.seconds
+    }
+}
+
+/// The first time after `after` at which `next` matches the local time of
+/// `zone`.
+///
+/// `next` computes the first match after a local time, both given as if the
+/// local time was UTC. See [`to_utc`] for local times which are skipped or
+/// repeated.
+pub(crate) fn next_in_zone<F>(
+    after: SystemTime,
+    zone: &dyn TimeZone,
+    mut next: F,
+) -> Option<SystemTime>
+where
+    F: FnMut(SystemTime) -> Option<SystemTime>,
+{
+    let mut local = shift(after, zone.utc_offset(after));
+
+    loop {
+        let next_local = next(local).filter(|&next_local| next_local > local)?;
+        let time = to_utc(next_local, zone);
+
+        // The first occurrence of a repeated local time may already be over.
+        if time > after {
+            return Some(time);
+        }
+
+        local = next_local;
+    }
+}
+
+/// The instant at which the local time of `zone` is `local`.
+///
+/// A local time skipped as clocks are moved forward maps onto the instant
+/// the length of the gap later, a local time which is repeated as clocks are
+/// turned back onto its first occurrence.
+fn to_utc(local: SystemTime, zone: &dyn TimeZone) -> SystemTime {
+    let day = Duration::from_secs(SECONDS_PER_DAY);
+    let before = zone.utc_offset(local.checked_sub(day).unwrap_or(local));
+    let after = zone.utc_offset(local.checked_add(day).unwrap_or(local));
+
+    [before, after]
+        .into_iter()
+        .filter_map(|offset| {
+            let time = shift(local, -offset);
+            (zone.utc_offset(time) == offset).then_some(time)
+        })
+        .min()
+        .unwrap_or_else(|| shift(local, -before))
+}
+
+/// Moves `time` by `seconds`, saturating at the bounds of [`SystemTime`].
+fn shift(time: SystemTime, seconds: i32) -> SystemTime {
+    let duration = Duration::from_secs(u64::from(seconds.unsigned_abs()));
+
+    if seconds < 0 {
+        time.checked_sub(duration).unwrap_or(time)
+    } else {
+        time.checked_add(duration).unwrap_or(time)
+    }
+}
+
+/// The first time after `after` which is `at` on any day.
+pub(crate) fn next_daily(at: TimeOfDay, after: SystemTime) -> SystemTime {
+    next_matching(at, after, 1, |_| true)
+}
+
+/// The first time after `after` which is `at` on a `weekday`.
+pub(crate) fn next_weekly(weekday: Weekday, at: TimeOfDay, after: SystemTime) -> SystemTime {
+    // The Unix epoch was a Thursday.
+    let thursday = Weekday::Thursday.index();
+    next_matching(at, after, 7, |day| (day + thursday) % 7 == weekday.index())
+}
+
+/// Searches the days from the one containing `after` for the first matching
+/// one whose `at` lies after `after`. Any `period` consecutive days contain a
+/// match.
+fn next_matching<F>(at: TimeOfDay, after: SystemTime, period: u64, matches: F) -> SystemTime
+where
+    F: Fn(u64) -> bool,
+{
+    let elapsed = after.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+    let today = elapsed.as_secs() / SECONDS_PER_DAY;
+
+    (today..=today + period)
+        .find_map(|day| {
+            let time = UNIX_EPOCH + Duration::from_secs(day * SECONDS_PER_DAY + at.seconds());
+            (time > after && matches(day)).then_some(time)
+        })
+        .unwrap_or(after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn occurrences_roll_over() {
+        // 2024-01-01 was a Monday.
+        let monday = UNIX_EPOCH + Duration::from_secs(19_723 * SECONDS_PER_DAY);
+        let hours = |hours: u64| Duration::from_secs(hours * 60 * 60);
+        let five_pm = TimeOfDay::new(17, 0, 0).unwrap();
+
+        assert_eq!(next_daily(five_pm, monday), monday + hours(17));
+        assert_eq!(
+            next_daily(five_pm, monday + hours(17)),
+            monday + hours(24 + 17)
+        );
+
+        let nine_am = TimeOfDay::new(9, 0, 0).unwrap();
+        assert_eq!(
+            next_weekly(Weekday::Monday, nine_am, monday),
+            monday + hours(9)
+        );
+        assert_eq!(
+            next_weekly(Weekday::Monday, nine_am, monday + hours(10)),
+            monday + hours(7 * 24 + 9)
+        );
+        assert_eq!(
+            next_weekly(Weekday::Sunday, TimeOfDay::MIDNIGHT, monday),
+            monday + hours(6 * 24)
+        );
+
+        assert_eq!(TimeOfDay::new(24, 0, 0), None);
+    }
+
+    fn hours(hours: u64) -> Duration {
+        Duration::from_secs(hours * 60 * 60)
+    }
+
+    fn day(days: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(days * SECONDS_PER_DAY)
+    }
+
+    // 2024-03-31 and 2024-10-27 in days since the epoch.
+    const SPRING: u64 = 19_813;
+    const AUTUMN: u64 = 20_023;
+
+    /// Central European time in 2024, moving from 02:00 to 03:00 on March 31
+    /// and from 03:00 back to 02:00 on October 27.
+    #[derive(Debug)]
+    struct Berlin;
+
+    impl TimeZone for Berlin {
+        fn utc_offset(&self, at: SystemTime) -> i32 {
+            let summer = day(SPRING) + hours(1)..day(AUTUMN) + hours(1);
+            if summer.contains(&at) {
+                2 * 60 * 60
+            } else {
+                60 * 60
+            }
+        }
+    }
+
+    #[test]
+    fn local_times_follow_daylight_saving_time() {
+        let daily = |at: TimeOfDay, after| {
+            next_in_zone(after, &Berlin, |local| Some(next_daily(at, local))).unwrap()
+        };
+        let half_past_two = TimeOfDay::new(2, 30, 0).unwrap();
+        let noon = TimeOfDay::new(12, 0, 0).unwrap();
+
+        assert_eq!(daily(noon, day(SPRING)), day(SPRING) + hours(10));
+        assert_eq!(daily(noon, day(SPRING + 1)), day(SPRING + 1) + hours(10));
+
+        // Skipped local times run later by the length of the gap, at 03:30.
+        assert_eq!(
+            daily(half_past_two, day(SPRING - 1) + hours(12)),
+            day(SPRING) + hours(1) + hours(1) / 2
+        );
+
+        // Repeated local times run once, at their first occurrence.
+        let first = day(AUTUMN) + hours(1) / 2;
+        assert_eq!(daily(half_past_two, day(AUTUMN - 1) + hours(12)), first);
+        assert_eq!(
+            daily(half_past_two, first),
+            day(AUTUMN + 1) + hours(1) + hours(1) / 2
+        );
+        assert_eq!(
+            daily(half_past_two, first + hours(1) / 2),
+            day(AUTUMN + 1) + hours(1) + hours(1) / 2
+        );
+
+        // 2024-01-01 was a Monday.
+        let monday = day(19_723);
+        let india = FixedOffset::east(5 * 60 * 60 + 30 * 60).unwrap();
+        let nine_am = TimeOfDay::new(9, 0, 0).unwrap();
+        assert_eq!(
+            next_in_zone(monday, &india, |local| {
+                Some(next_weekly(Weekday::Monday, nine_am, local))
+            }),
+            Some(monday + hours(3) + hours(1) / 2)
+        );
+        assert_eq!(FixedOffset::east(24 * 60 * 60), None);
+    }
+}
@@ -0,0 +1,196 @@
+#[cfg(feature = "tokio")]
+use std::{future::Future, pin::Pin};
+use std::{
+    hint,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Condvar, MutexGuard, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::Stbi;
+
+/// The time source used by a [`crate::Scheduler`].
+pub trait Clock {
+    fn now(&self) -> Stbi;
+
+    fn sleep(&self, duration: Duration);
+
+    /// Sleeps like [`Clock::sleep`], but wakes up early once `wakeup` is
+    /// notified. The lock of `guard` is released while sleeping.
+    ///
+    /// The default waits on `wakeup` in real time, virtual clocks should
+    /// advance by `duration` and return right away instead.
+    fn sleep_or_wake<'a, T>(
+        &self,
+        wakeup: &Condvar,
+        guard: MutexGuard<'a, T>,
+        duration: Duration,
+    ) -> MutexGuard<'a, T>
+    where
+        Self: Sized,
+    {
+        wakeup
+            .wait_timeout(guard, duration)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0
+    }
+
+    /// Busy-waits for `duration`, see [`crate::Scheduler::with_spin_threshold`].
+    ///
+    /// The default spins on [`Clock::now`], yielding to other threads in
+    /// between, virtual clocks should advance by `duration` instead.
+    fn spin(&self, duration: Duration) {
+        let until = self.now() + duration;
+
+        while until
+            .checked_since(self.now())
+            .is_some_and(|left| !left.is_zero())
+        {
+            hint::spin_loop();
+            thread::yield_now();
+        }
+    }
+
+    /// Sleeps like [`Clock::sleep`], but without blocking the thread, see
+    /// [`crate::AsyncScheduler`].
+    ///
+    /// The default waits on the timer of the tokio runtime, virtual clocks
+    /// should advance by `duration` once polled and only yield to the runtime
+    /// instead.
+    #[cfg(feature = "tokio")]
+    fn sleep_async(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    /// The lower bound for intervals of recurring tasks a scheduler on this
+    /// clock starts with, see [`crate::Scheduler::with_min_interval`].
+    ///
+    /// The default is [`crate::DEFAULT_MIN_INTERVAL`], clocks counting
+    /// shorter steps should return their step instead.
+    fn min_interval(&self) -> Duration {
+        crate::DEFAULT_MIN_INTERVAL
+    }
+
+    /// The wall-clock time, which wall-clock schedules like
+    /// [`crate::Schedule::At`] are mapped from.
+    fn system_now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// The default clock, backed by [`std::time::Instant`] and
+/// [`std::thread::sleep`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Stbi {
+        Stbi::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A virtual clock which only advances when told to.
+///
+/// Sleeping advances the virtual time instantly, which makes it possible to
+/// drive a scheduler without waiting on the wall-clock. The virtual
+/// wall-clock advances along, but can also be set independently to simulate
+/// it jumping. Clones share the same timeline.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    system_start: SystemTime,
+    elapsed_nanos: Arc<AtomicU64>,
+    /// How far the wall-clock was set ahead of (or behind) its start plus the
+    /// elapsed time.
+    system_offset_nanos: Arc<AtomicI64>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            system_start: SystemTime::now(),
+            elapsed_nanos: Arc::new(AtomicU64::new(0)),
+            system_offset_nanos: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    /// Sets the virtual wall-clock without advancing the monotonic time, like
+    /// an adjustment of the system clock would.
+    pub fn set_system_time(&self, time: SystemTime) {
+        let nanos = |duration: Duration| i64::try_from(duration.as_nanos()).unwrap_or(i64::MAX);
+        let offset = match time.duration_since(self.system_start + self.elapsed()) {
+            Ok(ahead) => nanos(ahead),
+            Err(behind) => -nanos(behind.duration()),
+        };
+
+        self.system_offset_nanos.store(offset, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        self.elapsed_nanos.fetch_add(nanos, Ordering::SeqCst);
+    }
+
+    /// The virtual time passed since this clock was created.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::SeqCst))
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Stbi {
+        Stbi(self.start) + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+
+    fn spin(&self, duration: Duration) {
+        self.advance(duration)
+    }
+
+    fn sleep_or_wake<'a, T>(
+        &self,
+        _: &Condvar,
+        guard: MutexGuard<'a, T>,
+        duration: Duration,
+    ) -> MutexGuard<'a, T> {
+        self.advance(duration);
+        guard
+    }
+
+    #[cfg(feature = "tokio")]
+    fn sleep_async(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let clock = self.clone();
+
+        Box::pin(async move {
+            clock.advance(duration);
+            tokio::task::yield_now().await;
+        })
+    }
+
+    fn system_now(&self) -> SystemTime {
+        let unadjusted = self.system_start + self.elapsed();
+        let offset = self.system_offset_nanos.load(Ordering::SeqCst);
+
+        match u64::try_from(offset) {
+            Ok(ahead) => unadjusted + Duration::from_nanos(ahead),
+            Err(_) => unadjusted - Duration::from_nanos(offset.unsigned_abs()),
+        }
+    }
+}
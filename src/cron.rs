@@ -0,0 +1,308 @@
+use std::{
+    error::Error,
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// How far ahead occurrences are searched. Covers the eight year gap between
+/// some leap days, so only expressions which never match give up.
+const MAX_DAYS: i64 = 366 * 8 + 1;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// An error parsing a cron expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CronError {
+    /// The expression does not consist of exactly five fields.
+    FieldCount(usize),
+    /// A field could not be parsed or is out of range.
+    InvalidField { field: &'static str, value: String },
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::FieldCount(count) => write!(f, "expected 5 fields, found {count}"),
+            Self::InvalidField { field, value } => write!(f, "invalid {field} field `{value}`"),
+        }
+    }
+}
+
+impl Error for CronError {}
+
+struct Field {
+    name: &'static str,
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+}
+
+const MINUTE: Field = Field {
+    name: "minute",
+    min: 0,
+    max: 59,
+    names: &[],
+};
+
+const HOUR: Field = Field {
+    name: "hour",
+    min: 0,
+    max: 23,
+    names: &[],
+};
+
+const DAY_OF_MONTH: Field = Field {
+    name: "day of month",
+    min: 1,
+    max: 31,
+    names: &[],
+};
+
+const MONTH: Field = Field {
+    name: "month",
+    min: 1,
+    max: 12,
+    names: &[
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ],
+};
+
+// Seven is accepted as an alias for sunday.
+const DAY_OF_WEEK: Field = Field {
+    name: "day of week",
+    min: 0,
+    max: 7,
+    names: &["sun", "mon", "tue", "wed", "thu", "fri", "sat"],
+};
+
+impl Field {
+    /// Parses the field into a bit set of the matching values.
+    fn parse(&self, value: &str) -> Result<u64, CronError> {
+        let error = || CronError::InvalidField {
+            field: self.name,
+            value: value.to_owned(),
+        };
+
+        let mut set = 0;
+
+        for item in value.split(',') {
+            let (range, step) = match item.split_once('/') {
+                Some((range, step)) => (range, step.parse().map_err(|_| error())?),
+                None => (item, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (self.min, self.max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (
+                    self.value(start).ok_or_else(error)?,
+                    self.value(end).ok_or_else(error)?,
+                )
+            } else {
+                let start = self.value(range).ok_or_else(error)?;
+                // `a/n` runs from `a` to the end of the range.
+                (start, if item.contains('/') { self.max } else { start })
+            };
+
+            if step == 0 || start > end {
+                return Err(error());
+            }
+
+            for value in (start..=end).step_by(step) {
+                set |= 1 << value;
+            }
+        }
+
+        Ok(set)
+    }
+
+    fn value(&self, value: &str) -> Option<u32> {
+        let value = match self
+            .names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(value))
+        {
+            Some(index) => index as u32 + self.min,
+            None => value.parse().ok()?,
+        };
+
+        (self.min..=self.max).contains(&value).then_some(value)
+    }
+}
+
+/// A parsed five field cron expression, evaluated in UTC.
+///
+/// Fields are `minute hour day-of-month month day-of-week` and support `*`,
+/// values, ranges `a-b`, lists `a,b` and steps `*/n`, `a-b/n` or `a/n`.
+/// Months and days of the week may also be given by their three letter
+/// names. As in most cron implementations, a task runs on days matching
+/// either the day of month or the day of week if both are restricted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Cron {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    /// Whether both day fields are restricted, which matches either of them.
+    either_day: bool,
+}
+
+impl Cron {
+    pub(crate) fn parse(expression: &str) -> Result<Self, CronError> {
+        let fields: Vec<_> = expression.split_whitespace().collect();
+
+        let [minutes, hours, days_of_month, months, days_of_week] = fields[..] else {
+            return Err(CronError::FieldCount(fields.len()));
+        };
+
+        let mut cron = Self {
+            minutes: MINUTE.parse(minutes)?,
+            hours: HOUR.parse(hours)?,
+            days_of_month: DAY_OF_MONTH.parse(days_of_month)?,
+            months: MONTH.parse(months)?,
+            days_of_week: DAY_OF_WEEK.parse(days_of_week)?,
+            either_day: !days_of_month.starts_with('*') && !days_of_week.starts_with('*'),
+        };
+
+        // Sunday is both zero and seven.
+        if cron.days_of_week & 1 << 7 != 0 {
+            cron.days_of_week |= 1;
+        }
+
+        Ok(cron)
+    }
+
+    /// The first matching minute strictly after the minute containing `time`.
+    pub(crate) fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        let elapsed = time.duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO);
+        let minute = (elapsed.as_secs() / 60) as i64 + 1;
+
+        let first_day = minute.div_euclid(MINUTES_PER_DAY);
+
+        for day in first_day..first_day + MAX_DAYS {
+            let start = if day == first_day {
+                minute.rem_euclid(MINUTES_PER_DAY)
+            } else {
+                0
+            };
+
+            if self.matches_day(day) {
+                for minute_of_day in start..MINUTES_PER_DAY {
+                    if contains(self.hours, minute_of_day / 60)
+                        && contains(self.minutes, minute_of_day % 60)
+                    {
+                        let minutes = (day * MINUTES_PER_DAY + minute_of_day) as u64;
+                        return UNIX_EPOCH.checked_add(Duration::from_secs(minutes * 60));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn matches_day(&self, days: i64) -> bool {
+        let (_, month, day) = civil_from_days(days);
+
+        // The epoch was a thursday.
+        let weekday = (days + 4).rem_euclid(7);
+        let day_of_month = contains(self.days_of_month, day);
+        let day_of_week = contains(self.days_of_week, weekday);
+
+        let day_matches = if self.either_day {
+            day_of_month || day_of_week
+        } else {
+            day_of_month && day_of_week
+        };
+
+        contains(self.months, month) && day_matches
+    }
+}
+
+fn contains(set: u64, value: impl Into<i64>) -> bool {
+    set & 1 << value.into() != 0
+}
+
+/// Converts days since the Unix epoch into a `(year, month, day)` date of the
+/// proleptic Gregorian calendar.
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(days: u64, minutes: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs((days * 24 * 60 + minutes) * 60)
+    }
+
+    // 2024-01-06 (a saturday) in days since the epoch.
+    const SATURDAY: u64 = 19_728;
+
+    #[test]
+    fn weekdays_at_half_past_eight() {
+        let cron = Cron::parse("30 8 * * mon-fri").unwrap();
+
+        assert_eq!(civil_from_days(SATURDAY as i64), (2024, 1, 6));
+        assert_eq!(
+            cron.next_after(utc(SATURDAY, 9 * 60)),
+            Some(utc(SATURDAY + 2, 8 * 60 + 30))
+        );
+        // An occurrence is not repeated, even from within its minute.
+        assert_eq!(
+            cron.next_after(utc(SATURDAY + 2, 8 * 60 + 30) + Duration::from_secs(10)),
+            Some(utc(SATURDAY + 3, 8 * 60 + 30))
+        );
+
+        let steps = Cron::parse("*/15 */6 1,15 * 0").unwrap();
+        // Both day fields are restricted, so sunday the 7th matches as well.
+        assert_eq!(
+            steps.next_after(utc(SATURDAY, 23 * 60 + 50)),
+            Some(utc(SATURDAY + 1, 0))
+        );
+        assert_eq!(
+            steps.next_after(utc(SATURDAY + 1, 0)),
+            Some(utc(SATURDAY + 1, 15))
+        );
+
+        assert_eq!(
+            Cron::parse("0 0 30 feb *").unwrap().next_after(utc(0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        assert_eq!(Cron::parse("* * * *"), Err(CronError::FieldCount(4)));
+
+        for expression in [
+            "60 * * * *",
+            "* * 0 * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * foo *",
+        ] {
+            assert!(
+                matches!(Cron::parse(expression), Err(CronError::InvalidField { .. })),
+                "{expression}"
+            );
+        }
+    }
+}
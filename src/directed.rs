@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use crate::{Job, Schedule, ScheduledTask, Task, Threading};
+
+/// The function of a [`Schedule::SelfDirected`] task.
+pub(crate) type DirectedFunction<'a> = Box<dyn FnMut() -> Option<Duration> + Send + 'a>;
+
+impl Schedule {
+    /// Creates a [`Schedule::SelfDirected`] task which first runs after
+    /// `first`, each execution of `f` returning the delay until the next one
+    /// or `None` to stop.
+    pub fn self_directed<'a, F>(first: Duration, f: F) -> Task<'a>
+    where
+        F: 'a + Send + FnMut() -> Option<Duration>,
+    {
+        let schedule = Self::SelfDirected {
+            delay: first,
+            next: Some(first),
+        };
+
+        Task::new(
+            schedule,
+            Job::Directed {
+                f: Box::new(f),
+                returned: None,
+            },
+        )
+    }
+
+    /// Records the delay returned by an execution of the schedule in effect.
+    fn direct(&mut self, delay: Option<Duration>) {
+        match self {
+            Self::SelfDirected { next, .. } => *next = delay,
+            Self::Then(first, _) => first.direct(delay),
+            _ => {}
+        }
+    }
+}
+
+impl<T: Threading> Job<'_, T> {
+    /// Takes the delay returned by the last execution of a directed function
+    /// unless it was taken already.
+    ///
+    /// Functions which are executing on a worker are not waited for, their
+    /// delay is taken the next time.
+    pub(crate) fn take_directed(&mut self) -> Option<Option<Duration>> {
+        match self {
+            Self::Directed { returned, .. } => returned.take(),
+            Self::Shared(job) => job.try_lock().ok()?.take_directed(),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Threading> ScheduledTask<'_, T> {
+    /// Moves the delay returned by the last execution of the task into its
+    /// schedule.
+    pub(crate) fn direct(&mut self) {
+        if let Some(delay) = self.task.f.take_directed() {
+            self.task.schedule.direct(delay);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    };
+
+    use super::*;
+    use crate::{Clock, MockClock, Scheduler};
+
+    #[test]
+    fn executions_choose_the_next_delay() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let runs = Arc::new(AtomicU64::new(0));
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::self_directed(Duration::from_secs(1), move || {
+                let runs = runs.fetch_add(1, Ordering::SeqCst) + 1;
+                (runs < 3).then(|| Duration::from_secs(runs * 10))
+            })
+        };
+
+        Scheduler::with_clock(clock.clone(), vec![task]).run();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(clock.now().since(start), Duration::from_secs(31));
+    }
+
+    #[test]
+    fn out_of_band_executions_keep_the_cadence() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let start = clock.now();
+        let enabled = Arc::new(AtomicBool::new(true));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        // Executions at odd seconds, i.e. the out-of-band ones, stop the task.
+        let task = Schedule::self_directed(second * 2, {
+            let clock = clock.clone();
+            move || {
+                clock
+                    .now()
+                    .since(start)
+                    .as_secs()
+                    .is_multiple_of(2)
+                    .then_some(second * 2)
+            }
+        });
+        let id = scheduler.add(task.when({
+            let enabled = enabled.clone();
+            move || enabled.load(Ordering::SeqCst)
+        }));
+
+        clock.advance(second);
+        assert!(scheduler.fire_now(id));
+        assert_eq!(scheduler.next_fire(id), Some(start + second * 2));
+
+        clock.advance(second);
+        scheduler.run_pending();
+        clock.advance(second);
+        assert!(scheduler.fire_now(id));
+
+        // A skipped execution keeps the delay of the last regular one.
+        enabled.store(false, Ordering::SeqCst);
+        clock.advance(second);
+        scheduler.run_pending();
+        assert_eq!(scheduler.next_fire(id), Some(start + second * 6));
+    }
+
+    #[test]
+    fn replaced_functions_keep_the_last_delay() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        let runs = Arc::new(AtomicU64::new(0));
+
+        let id = scheduler.add(Schedule::self_directed(second, || {
+            Some(Duration::from_secs(5))
+        }));
+        clock.advance(second);
+        scheduler.run_pending();
+
+        let replaced = {
+            let runs = runs.clone();
+            scheduler.replace_closure(
+                id,
+                Box::new(move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }),
+            )
+        };
+        assert!(replaced);
+
+        scheduler.run_until(clock.now() + Duration::from_secs(15));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(
+            scheduler.next_fire(id),
+            Some(clock.now() + Duration::from_secs(5))
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    panic,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{Clock, Scheduler, Task, TaskId};
+
+enum Command {
+    Add(TaskId, Task<'static>),
+    Cancel(TaskId, Sender<bool>),
+    FireNow(TaskId, Sender<bool>),
+    Shutdown,
+}
+
+/// Controls a [`Scheduler`] running on its own thread, see
+/// [`Scheduler::spawn`].
+pub struct SchedulerHandle {
+    commands: Sender<Command>,
+    next_id: AtomicU64,
+    thread: JoinHandle<()>,
+}
+
+impl SchedulerHandle {
+    /// Schedules an additional task relative to the time the scheduler
+    /// receives it.
+    pub fn add(&self, task: Task<'static>) -> TaskId {
+        let id = TaskId(self.next_id.fetch_add(1, Ordering::Relaxed));
+
+        // If the scheduler already stopped there is nothing left to run the
+        // task, so it is silently dropped.
+        let _ = self.commands.send(Command::Add(id, task));
+
+        id
+    }
+
+    /// Cancels a task, see [`Scheduler::cancel`].
+    ///
+    /// Blocks until the scheduler processed the request, which only happens
+    /// in between executing tasks.
+    pub fn cancel(&self, id: TaskId) -> bool {
+        let (reply, response) = mpsc::channel();
+
+        if self.commands.send(Command::Cancel(id, reply)).is_err() {
+            return false;
+        }
+
+        response.recv().unwrap_or(false)
+    }
+
+    /// Executes a task once right away, see [`Scheduler::fire_now`].
+    ///
+    /// Blocks until the scheduler executed the task, which only happens in
+    /// between executing other tasks.
+    pub fn fire_now(&self, id: TaskId) -> bool {
+        let (reply, response) = mpsc::channel();
+
+        if self.commands.send(Command::FireNow(id, reply)).is_err() {
+            return false;
+        }
+
+        response.recv().unwrap_or(false)
+    }
+
+    /// Stops the scheduler after the currently executing task and waits for
+    /// its thread to exit.
+    ///
+    /// Resumes the panic if the scheduler thread panicked.
+    pub fn shutdown(self) {
+        let _ = self.commands.send(Command::Shutdown);
+
+        if let Err(payload) = self.thread.join() {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+impl Scheduler<'static> {
+    /// Moves the scheduler onto its own thread.
+    ///
+    /// The returned handle adds and cancels tasks while the scheduler runs.
+    /// Without pending tasks the thread waits for new ones instead of
+    /// returning. Dropping the handle detaches the thread, which then runs
+    /// until no tasks are left.
+    pub fn spawn(self) -> SchedulerHandle {
+        let (commands, receiver) = mpsc::channel();
+        let next_id = AtomicU64::new(self.next_id);
+
+        let thread = thread::Builder::new()
+            .name(String::from("casched"))
+            .spawn(move || self.serve(receiver))
+            .expect("Failed to spawn scheduler thread");
+
+        SchedulerHandle {
+            commands,
+            next_id,
+            thread,
+        }
+    }
+
+    fn serve(mut self, commands: Receiver<Command>) {
+        let mut connected = true;
+
+        loop {
+            let next = self.run_pending();
+
+            // Waiting on the channel instead of sleeping lets commands
+            // interrupt the wait for the next task.
+            let command = match (next, connected) {
+                (Some(diff), true) => commands.recv_timeout(diff.min(self.sleep_slice)),
+                (None, true) => commands.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                (Some(diff), false) => {
+                    self.sleep(diff);
+                    continue;
+                }
+                (None, false) => return,
+            };
+
+            match command {
+                Ok(Command::Add(id, task)) => {
+                    self.insert_with_id(id, task, self.clock.now(), self.clock.system_now())
+                }
+                Ok(Command::Cancel(id, reply)) => {
+                    let _ = reply.send(self.cancel(id));
+                }
+                Ok(Command::FireNow(id, reply)) => {
+                    let _ = reply.send(self.fire_now(id));
+                }
+                Ok(Command::Shutdown) => return,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => connected = false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::Schedule;
+
+    #[test]
+    fn spawned_scheduler_is_controlled_by_handle() {
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let every = {
+            let runs = runs.clone();
+            Schedule::Every(Duration::from_millis(5)).with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        let mut scheduler = Scheduler::new();
+        let every = scheduler.add(every);
+        let handle = scheduler.spawn();
+
+        let (sender, receiver) = mpsc::channel();
+        let once = handle.add(Schedule::Once(None).with(move || sender.send(()).unwrap()));
+        assert_ne!(once, every);
+
+        receiver.recv_timeout(Duration::from_secs(1)).unwrap();
+        while runs.load(Ordering::SeqCst) == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let fired = runs.load(Ordering::SeqCst);
+        assert!(handle.fire_now(every));
+        assert!(runs.load(Ordering::SeqCst) > fired);
+
+        assert!(handle.cancel(every));
+        assert!(!handle.cancel(every));
+        assert!(!handle.fire_now(every));
+
+        let stopped = runs.load(Ordering::SeqCst);
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(runs.load(Ordering::SeqCst), stopped);
+
+        handle.shutdown();
+    }
+}
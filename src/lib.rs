@@ -1,18 +1,81 @@
 //! TODO
-//! - Have lifetimed scheduler (e.g. non static functions)
 //! - Measure jitter / long running functions to make predictions?
-//!
-//! - Multiple scheduler implementations (e.g. Instant, tick/interval based)
+
+#[cfg(feature = "tokio")]
+mod async_scheduler;
+mod builder;
+mod calendar;
+mod clock;
+mod cron;
+mod directed;
+mod handle;
+mod logging;
+mod parse;
+mod pool;
+mod queue;
+mod rate_limit;
+mod recorder;
+mod retry;
+mod rng;
+mod shared;
+mod shutdown;
+mod sink;
+mod spec;
+mod state;
+mod stats;
+mod status;
+mod threading;
+mod tick;
+mod validate;
+
+#[cfg(feature = "tokio")]
+pub use async_scheduler::{AsyncScheduler, AsyncSchedulerHandle, AsyncTaskFunction};
+pub use builder::{TaskBuildError, TaskBuilder};
+pub use calendar::{FixedOffset, TimeOfDay, TimeZone, Weekday};
+pub use clock::{Clock, MockClock, SystemClock};
+pub use cron::CronError;
+pub use handle::SchedulerHandle;
+pub use parse::ParseScheduleError;
+pub use recorder::Recorder;
+pub use retry::RetryPolicy;
+pub use shared::SharedScheduler;
+pub use shutdown::{ShutdownToken, StopHandle};
+pub use spec::{ScheduleSpec, TaskSpec};
+pub use state::FinalState;
+pub use stats::TaskStats;
+pub use status::{SchedulerState, StateHandle};
+pub use threading::{Concurrent, Local, LocalScheduler, LocalTask, Threading};
+pub use tick::TickClock;
+pub use validate::ScheduleError;
+
+use cron::Cron;
+use directed::DirectedFunction;
+use logging::{debug, trace, warn};
+use pool::{Failure, Pool};
+use queue::Queue;
+use rate_limit::RateLimit;
+use rng::Rng;
 
 use std::{
-    collections::BinaryHeap,
+    any::Any,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
     ops::Add,
-    time::{Duration, Instant},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, PoisonError},
+    task::Waker,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 /// An [`std::time::Instant`] wrapper with the main purpose of reversing the
 /// ordering.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// As instants have no absolute representation, `Stbi` is displayed relative
+/// to [`Stbi::now`], e.g. `+4.20s` for a time in the future and `-1.50s` for
+/// one in the past. The precision defaults to two digits.
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct Stbi(Instant);
 
 impl Stbi {
@@ -20,14 +83,72 @@ impl Stbi {
         Self(Instant::now())
     }
 
+    /// The time elapsed from `earlier` to `self`, saturating to zero if
+    /// `earlier` is actually later.
     pub fn since(&self, earlier: Self) -> Duration {
-        self.0.duration_since(earlier.0)
+        self.0.saturating_duration_since(earlier.0)
+    }
+
+    /// The time elapsed from `earlier` to `self`, or `None` if `earlier` is
+    /// actually later.
+    pub fn checked_since(&self, earlier: Self) -> Option<Duration> {
+        self.0.checked_duration_since(earlier.0)
+    }
+
+    pub fn from_instant(instant: Instant) -> Self {
+        Self(instant)
+    }
+
+    /// The wrapped instant, which is ordered as usual.
+    pub fn into_instant(self) -> Instant {
+        self.0
+    }
+
+    /// The time elapsed since `self` according to [`Instant::now`], zero if
+    /// `self` lies in the future.
+    ///
+    /// Times read from a [`MockClock`] are not related to the real time, use
+    /// [`Stbi::since`] with the reading of the clock instead.
+    pub fn elapsed(&self) -> Duration {
+        Self::now().since(*self)
+    }
+}
+
+impl fmt::Display for Stbi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let now = Self::now();
+        let precision = f.precision().unwrap_or(2);
+
+        match self.checked_since(now) {
+            Some(ahead) => write!(f, "+{:.precision$}s", ahead.as_secs_f64()),
+            None => write!(f, "-{:.precision$}s", now.since(*self).as_secs_f64()),
+        }
+    }
+}
+
+impl fmt::Debug for Stbi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Stbi")
+            .field(&format_args!("{self}"))
+            .finish()
+    }
+}
+
+impl From<Instant> for Stbi {
+    fn from(instant: Instant) -> Self {
+        Self::from_instant(instant)
+    }
+}
+
+impl From<Stbi> for Instant {
+    fn from(stbi: Stbi) -> Self {
+        stbi.into_instant()
     }
 }
 
 impl PartialOrd for Stbi {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        (other.0).partial_cmp(&self.0)
+        Some(self.cmp(other))
     }
 }
 
@@ -37,168 +158,3465 @@ impl Ord for Stbi {
     }
 }
 
+/// The offset used in place of durations which can not be represented by an
+/// [`Instant`].
+const FAR_FUTURE: Duration = Duration::from_secs(60 * 60 * 24 * 365 * 30);
+
 impl Add<Duration> for Stbi {
     type Output = Stbi;
 
+    /// Saturates to a far-future instant instead of panicking on overflow.
     fn add(self, rhs: Duration) -> Self::Output {
-        Self(self.0.add(rhs))
+        self.0
+            .checked_add(rhs)
+            .or_else(|| self.0.checked_add(FAR_FUTURE))
+            .map_or(self, Self)
     }
 }
 
 pub enum Schedule {
     Once(Option<Duration>),
+    /// Runs once at the given instant, or immediately if it already passed.
+    ///
+    /// The instant must stem from the timeline of the scheduler's clock,
+    /// e.g. [`Instant::now`] for the [`SystemClock`].
+    OnceAt(Instant),
+    /// Runs once at the given wall-clock time, or immediately if it already
+    /// passed.
+    ///
+    /// The target stays the wall-clock time rather than a delay computed when
+    /// the task was added: whenever the scheduler wakes up and notices that
+    /// the system clock moved against the monotonic clock, e.g. after the
+    /// machine was suspended, the remaining delay is derived from
+    /// [`Clock::system_now`] again.
+    At(SystemTime),
+    /// Runs every interval, skipping executions which were missed because
+    /// the scheduler fell behind.
     Every(Duration),
+    /// Runs on a fixed grid of intervals; missed executions are caught up on
+    /// back-to-back.
+    FixedRate(Duration),
+    /// Waits the interval after each execution completed.
+    FixedDelay(Duration),
+    /// Like [`Schedule::Every`], but on a grid of intervals counted from the
+    /// Unix epoch, e.g. at the start of every minute for 60 seconds.
+    EveryAligned(Duration),
+    /// Like [`Schedule::Every`], but the first execution is after `offset`
+    /// instead of a whole interval, e.g. to stagger tasks of the same
+    /// interval so they do not execute together.
+    ///
+    /// The following executions continue on the grid of intervals from the
+    /// first one. The offset should be shorter than the interval, see
+    /// [`Schedule::validate`].
+    EveryWithOffset {
+        interval: Duration,
+        offset: Duration,
+    },
+    /// Like [`Schedule::Every`], but every execution is randomly moved by up
+    /// to `jitter` in either direction.
+    EveryJittered {
+        interval: Duration,
+        jitter: Duration,
+    },
+    /// Waits a new random delay in `[min, max]` before each execution.
+    ///
+    /// Unlike [`Schedule::EveryJittered`] there is no base interval, every
+    /// delay is drawn uniformly from the whole range. Use
+    /// [`Schedule::random_interval`] to validate the bounds.
+    RandomInterval {
+        min: Duration,
+        max: Duration,
+    },
+    /// Runs `count` times in total, the first time after `interval`.
+    ///
+    /// A `count` of zero never runs; such tasks are discarded when they are
+    /// added to a [`Scheduler`].
     Counted {
         interval: Duration,
         count: usize,
     },
+    /// Like [`Schedule::Counted`], but also stops once the next execution
+    /// would lie after `until`, whichever comes first.
+    ///
+    /// Like [`Schedule::OnceAt`], `until` must stem from the timeline of the
+    /// scheduler's clock. Only the executions after the first one are bound
+    /// by it.
+    CountedOrUntil {
+        interval: Duration,
+        count: usize,
+        until: Stbi,
+    },
     Checked {
         interval: Duration,
-        check: Box<dyn FnMut() -> bool>,
+        check: Box<dyn FnMut() -> bool + Send>,
+    },
+    /// Computes the delay before each execution from the number of
+    /// executions so far, stopping once `next` returns `None`.
+    ///
+    /// `delay` is the delay before the upcoming execution and `runs` should
+    /// start at zero; see [`Schedule::dynamic`].
+    Dynamic {
+        delay: Duration,
+        runs: u32,
+        next: Box<dyn FnMut(u32) -> Option<Duration> + Send>,
+    },
+    /// Runs after the delay returned by the previous execution of the task,
+    /// stopping once it returns `None`; see [`Schedule::self_directed`].
+    ///
+    /// `delay` is the delay before the upcoming execution and `next` the
+    /// delay returned by the last execution, which the task waits after the
+    /// upcoming one. Executions skipped by [`Task::when`] keep it. Tasks
+    /// executed by workers are rescheduled as they are dispatched, so they
+    /// follow the delay returned by the last execution which completed
+    /// before.
+    SelfDirected {
+        delay: Duration,
+        next: Option<Duration>,
+    },
+    /// Runs whenever the five field cron expression matches, see
+    /// [`Schedule::cron`].
+    ///
+    /// Expressions are evaluated in UTC, so local daylight saving time
+    /// transitions do not affect them, see [`Schedule::CronIn`] for local
+    /// time. Tasks with an invalid expression are discarded when they are
+    /// added to a [`Scheduler`].
+    Cron(String),
+    /// Like [`Schedule::Cron`], but evaluated in the local time of `zone`,
+    /// see [`Schedule::cron_in`].
+    ///
+    /// Daylight saving time is handled like by [`Schedule::Daily`], so
+    /// matches within a skipped hour run the length of the gap later and
+    /// matches within a repeated hour run only during its first pass.
+    CronIn {
+        expression: String,
+        zone: Arc<dyn TimeZone>,
+    },
+    /// Runs every day at `at` in UTC.
+    ///
+    /// Like [`Schedule::Cron`] the time does not follow the local time zone:
+    /// a daily task keeps its UTC time across daylight saving time
+    /// transitions, so its local time shifts by the change of the offset.
+    /// See [`Schedule::Daily`] for local time.
+    DailyUtc {
+        at: TimeOfDay,
+    },
+    /// Runs every week on `weekday` at `at` in UTC, which does not follow
+    /// local time or daylight saving time like [`Schedule::DailyUtc`].
+    WeeklyUtc {
+        weekday: Weekday,
+        at: TimeOfDay,
     },
+    /// Runs every day at `at` in the local time of `zone`.
+    ///
+    /// If clocks are moved forward across `at`, e.g. from 02:00 to 03:00
+    /// for a task at 02:30, the task runs the length of the gap later, at
+    /// 03:30. If clocks are turned back across `at`, the task runs once, at
+    /// the first occurrence of `at`.
+    Daily {
+        at: TimeOfDay,
+        zone: Arc<dyn TimeZone>,
+    },
+    /// Runs every week on `weekday` at `at` in the local time of `zone`,
+    /// handling daylight saving time like [`Schedule::Daily`].
+    Weekly {
+        weekday: Weekday,
+        at: TimeOfDay,
+        zone: Arc<dyn TimeZone>,
+    },
+    /// Follows the first schedule until it is exhausted, then continues with
+    /// the second one, see [`Schedule::then`].
+    ///
+    /// The first execution of the second schedule is computed from the time
+    /// the last execution of the first one completed.
+    Then(Box<Schedule>, Box<Schedule>),
+    /// Never runs, keeping the task scheduled as a disabled placeholder.
+    ///
+    /// It can be activated by [`Scheduler::reschedule_task`]. While such a
+    /// task is scheduled [`Scheduler::run`] does not return, but the
+    /// scheduler only wakes up once per sleep slice.
+    Never,
+}
+
+/// The variants of [`Schedule`] without their parameters, see
+/// [`Schedule::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScheduleKind {
+    Once,
+    OnceAt,
+    At,
+    Every,
+    FixedRate,
+    FixedDelay,
+    EveryAligned,
+    EveryWithOffset,
+    EveryJittered,
+    RandomInterval,
+    Counted,
+    CountedOrUntil,
+    Checked,
+    Dynamic,
+    SelfDirected,
+    Cron,
+    CronIn,
+    DailyUtc,
+    WeeklyUtc,
+    Daily,
+    Weekly,
+    Then,
+    Never,
+}
+
+impl ScheduleKind {
+    /// The name of the variant, e.g. `"Every"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Once => "Once",
+            Self::OnceAt => "OnceAt",
+            Self::At => "At",
+            Self::Every => "Every",
+            Self::FixedRate => "FixedRate",
+            Self::FixedDelay => "FixedDelay",
+            Self::EveryAligned => "EveryAligned",
+            Self::EveryWithOffset => "EveryWithOffset",
+            Self::EveryJittered => "EveryJittered",
+            Self::RandomInterval => "RandomInterval",
+            Self::Counted => "Counted",
+            Self::CountedOrUntil => "CountedOrUntil",
+            Self::Checked => "Checked",
+            Self::Dynamic => "Dynamic",
+            Self::SelfDirected => "SelfDirected",
+            Self::Cron => "Cron",
+            Self::CronIn => "CronIn",
+            Self::DailyUtc => "DailyUtc",
+            Self::WeeklyUtc => "WeeklyUtc",
+            Self::Daily => "Daily",
+            Self::Weekly => "Weekly",
+            Self::Then => "Then",
+            Self::Never => "Never",
+        }
+    }
+}
+
+impl fmt::Display for ScheduleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = self.kind().name();
+
+        match self {
+            Self::Once(delay) => f.debug_tuple(kind).field(delay).finish(),
+            Self::OnceAt(instant) => f.debug_tuple(kind).field(instant).finish(),
+            Self::At(time) => f.debug_tuple(kind).field(time).finish(),
+            Self::Every(interval) => f.debug_tuple(kind).field(interval).finish(),
+            Self::FixedRate(interval) => f.debug_tuple(kind).field(interval).finish(),
+            Self::FixedDelay(interval) => f.debug_tuple(kind).field(interval).finish(),
+            Self::EveryAligned(interval) => f.debug_tuple(kind).field(interval).finish(),
+            Self::EveryWithOffset { interval, offset } => f
+                .debug_struct(kind)
+                .field("interval", interval)
+                .field("offset", offset)
+                .finish(),
+            Self::EveryJittered { interval, jitter } => f
+                .debug_struct(kind)
+                .field("interval", interval)
+                .field("jitter", jitter)
+                .finish(),
+            Self::RandomInterval { min, max } => f
+                .debug_struct(kind)
+                .field("min", min)
+                .field("max", max)
+                .finish(),
+            Self::Counted { interval, count } => f
+                .debug_struct(kind)
+                .field("interval", interval)
+                .field("count", count)
+                .finish(),
+            Self::CountedOrUntil {
+                interval,
+                count,
+                until,
+            } => f
+                .debug_struct(kind)
+                .field("interval", interval)
+                .field("count", count)
+                .field("until", until)
+                .finish(),
+            Self::Checked { interval, .. } => f
+                .debug_struct(kind)
+                .field("interval", interval)
+                .finish_non_exhaustive(),
+            Self::Dynamic { delay, runs, .. } => f
+                .debug_struct(kind)
+                .field("delay", delay)
+                .field("runs", runs)
+                .finish_non_exhaustive(),
+            Self::SelfDirected { delay, next } => f
+                .debug_struct(kind)
+                .field("delay", delay)
+                .field("next", next)
+                .finish(),
+            Self::Cron(expression) => f.debug_tuple(kind).field(expression).finish(),
+            Self::DailyUtc { at } => f.debug_struct(kind).field("at", at).finish(),
+            Self::CronIn { expression, zone } => f
+                .debug_struct(kind)
+                .field("expression", expression)
+                .field("zone", zone)
+                .finish(),
+            Self::WeeklyUtc { weekday, at } => f
+                .debug_struct(kind)
+                .field("weekday", weekday)
+                .field("at", at)
+                .finish(),
+            Self::Daily { at, zone } => f
+                .debug_struct(kind)
+                .field("at", at)
+                .field("zone", zone)
+                .finish(),
+            Self::Weekly { weekday, at, zone } => f
+                .debug_struct(kind)
+                .field("weekday", weekday)
+                .field("at", at)
+                .field("zone", zone)
+                .finish(),
+            Self::Then(first, second) => f.debug_tuple(kind).field(first).field(second).finish(),
+            Self::Never => f.write_str(kind),
+        }
+    }
 }
 
 impl Schedule {
-    pub fn reschedule(mut self) -> Option<Self> {
+    /// The variant of the schedule without its parameters.
+    pub fn kind(&self) -> ScheduleKind {
+        match self {
+            Self::Once(_) => ScheduleKind::Once,
+            Self::OnceAt(_) => ScheduleKind::OnceAt,
+            Self::At(_) => ScheduleKind::At,
+            Self::Every(_) => ScheduleKind::Every,
+            Self::FixedRate(_) => ScheduleKind::FixedRate,
+            Self::FixedDelay(_) => ScheduleKind::FixedDelay,
+            Self::EveryAligned(_) => ScheduleKind::EveryAligned,
+            Self::EveryWithOffset { .. } => ScheduleKind::EveryWithOffset,
+            Self::EveryJittered { .. } => ScheduleKind::EveryJittered,
+            Self::RandomInterval { .. } => ScheduleKind::RandomInterval,
+            Self::Counted { .. } => ScheduleKind::Counted,
+            Self::CountedOrUntil { .. } => ScheduleKind::CountedOrUntil,
+            Self::Checked { .. } => ScheduleKind::Checked,
+            Self::Dynamic { .. } => ScheduleKind::Dynamic,
+            Self::SelfDirected { .. } => ScheduleKind::SelfDirected,
+            Self::Cron(_) => ScheduleKind::Cron,
+            Self::CronIn { .. } => ScheduleKind::CronIn,
+            Self::DailyUtc { .. } => ScheduleKind::DailyUtc,
+            Self::WeeklyUtc { .. } => ScheduleKind::WeeklyUtc,
+            Self::Daily { .. } => ScheduleKind::Daily,
+            Self::Weekly { .. } => ScheduleKind::Weekly,
+            Self::Then(..) => ScheduleKind::Then,
+            Self::Never => ScheduleKind::Never,
+        }
+    }
+
+    /// Creates a [`Schedule::Once`] which runs after `delay`.
+    pub fn once(delay: Duration) -> Self {
+        Self::Once(Some(delay))
+    }
+
+    /// Creates a [`Schedule::Once`] which runs right away.
+    pub fn immediately() -> Self {
+        Self::Once(None)
+    }
+
+    /// Creates a [`Schedule::Every`].
+    pub fn every(interval: Duration) -> Self {
+        Self::Every(interval)
+    }
+
+    /// Creates a [`Schedule::Counted`] which runs `count` times, every
+    /// `interval`.
+    pub fn counted(count: usize, interval: Duration) -> Self {
+        Self::Counted { interval, count }
+    }
+
+    /// Creates a [`Schedule::Dynamic`] which first runs after `first`.
+    pub fn dynamic<F>(first: Duration, next: F) -> Self
+    where
+        F: 'static + Send + FnMut(u32) -> Option<Duration>,
+    {
+        Self::Dynamic {
+            delay: first,
+            runs: 0,
+            next: Box::new(next),
+        }
+    }
+
+    /// Creates a [`Schedule::RandomInterval`], or returns `None` if `min`
+    /// exceeds `max`.
+    pub fn random_interval(min: Duration, max: Duration) -> Option<Self> {
+        (min <= max).then_some(Self::RandomInterval { min, max })
+    }
+
+    /// Creates a [`Schedule::Cron`], validating the expression.
+    ///
+    /// Fields are `minute hour day-of-month month day-of-week` and support
+    /// `*`, values, ranges `a-b`, lists `a,b` and steps `*/n`, `a-b/n` or
+    /// `a/n`. Months and days of the week may also be given by their three
+    /// letter names. If both day fields are restricted, days matching either
+    /// of them are included.
+    pub fn cron(expression: impl Into<String>) -> Result<Self, CronError> {
+        let expression = expression.into();
+        Cron::parse(&expression)?;
+        Ok(Self::Cron(expression))
+    }
+
+    /// Like [`Schedule::cron`], but creates a [`Schedule::CronIn`] evaluated
+    /// in the local time of `zone`.
+    pub fn cron_in(
+        expression: impl Into<String>,
+        zone: impl TimeZone + 'static,
+    ) -> Result<Self, CronError> {
+        let expression = expression.into();
+        Cron::parse(&expression)?;
+        Ok(Self::CronIn {
+            expression,
+            zone: Arc::new(zone),
+        })
+    }
+
+    /// Creates a [`Schedule::Then`] which continues with `next` once this
+    /// schedule is exhausted, e.g. running after an initial delay and then
+    /// every second with `Schedule::Once(Some(delay)).then(Schedule::Every(second))`.
+    pub fn then(self, next: Schedule) -> Self {
+        Self::Then(Box::new(self), Box::new(next))
+    }
+
+    pub fn reschedule(self) -> Option<Self> {
+        self.reschedule_after(SystemTime::now())
+    }
+
+    /// Like [`Schedule::reschedule`], `system_now` being the wall-clock time
+    /// calendar schedules are checked for further occurrences at.
+    fn reschedule_after(mut self, system_now: SystemTime) -> Option<Self> {
+        if let Self::Then(first, second) = self {
+            return match first.reschedule_after(system_now) {
+                Some(first) => Some(Self::Then(Box::new(first), second)),
+                None if second.is_exhausted(system_now) => None,
+                None => Some(*second),
+            };
+        }
+
         match &mut self {
-            Self::Every(_) => Some(self),
-            Self::Counted { count, .. } if *count > 1 => {
+            Self::Every(_)
+            | Self::FixedRate(_)
+            | Self::FixedDelay(_)
+            | Self::EveryAligned(_)
+            | Self::EveryWithOffset { .. }
+            | Self::EveryJittered { .. }
+            | Self::RandomInterval { .. }
+            | Self::Cron(_)
+            | Self::CronIn { .. }
+            | Self::DailyUtc { .. }
+            | Self::WeeklyUtc { .. }
+            | Self::Daily { .. }
+            | Self::Weekly { .. } => Some(self),
+            Self::Counted { count, .. } | Self::CountedOrUntil { count, .. } if *count > 1 => {
                 *count -= 1;
                 Some(self)
             }
             Self::Checked { check, .. } => check().then_some(self),
+            Self::Dynamic { delay, runs, next } => {
+                *runs = runs.saturating_add(1);
+                *delay = next(*runs)?;
+                Some(self)
+            }
+            Self::SelfDirected { delay, next } => {
+                *delay = (*next)?;
+                Some(self)
+            }
+            _ => None,
+        }
+    }
+
+    /// The number of executions left for [`Schedule::Counted`] and
+    /// [`Schedule::CountedOrUntil`], including the next pending one.
+    pub fn remaining(&self) -> Option<usize> {
+        match self {
+            Self::Counted { count, .. } | Self::CountedOrUntil { count, .. } => Some(*count),
             _ => None,
         }
     }
 
+    /// The duration stored in the schedule, i.e. its delay or interval.
+    ///
+    /// [`Schedule::RandomInterval`] returns its minimum and
+    /// [`Schedule::Never`] a delay decades away. Wall-clock and calendar
+    /// schedules such as [`Schedule::At`] or [`Schedule::Cron`] store no
+    /// duration and return zero.
     pub fn as_duration(&self) -> &Duration {
         match self {
             Self::Once(duration) => duration.as_ref().unwrap_or(&Duration::ZERO),
-            Self::Every(d) => d,
-            Self::Counted { interval, .. } => interval,
+            Self::Every(d) | Self::FixedRate(d) | Self::FixedDelay(d) | Self::EveryAligned(d) => d,
+            Self::EveryWithOffset { interval, .. } | Self::EveryJittered { interval, .. } => {
+                interval
+            }
+            Self::RandomInterval { min, .. } => min,
+            Self::Counted { interval, .. } | Self::CountedOrUntil { interval, .. } => interval,
             Self::Checked { interval, .. } => interval,
+            Self::Dynamic { delay, .. } | Self::SelfDirected { delay, .. } => delay,
+            Self::OnceAt(_)
+            | Self::At(_)
+            | Self::Cron(_)
+            | Self::CronIn { .. }
+            | Self::DailyUtc { .. }
+            | Self::WeeklyUtc { .. }
+            | Self::Daily { .. }
+            | Self::Weekly { .. } => &Duration::ZERO,
+            Self::Then(first, _) => first.as_duration(),
+            Self::Never => &FAR_FUTURE,
+        }
+    }
+
+    /// The delay from `now` until the next execution, `system_now` being the
+    /// wall-clock time sampled together with `now`.
+    fn delay(&self, now: Stbi, system_now: SystemTime, rng: &mut Rng) -> Duration {
+        match self {
+            Self::Once(duration) => duration.unwrap_or(Duration::ZERO),
+            Self::OnceAt(instant) => Stbi(*instant).since(now),
+            Self::At(time) => Self::until(*time, system_now),
+            Self::Every(d) | Self::FixedRate(d) | Self::FixedDelay(d) | Self::EveryAligned(d) => *d,
+            Self::EveryWithOffset { interval, .. } | Self::EveryJittered { interval, .. } => {
+                *interval
+            }
+            Self::RandomInterval { min, max } => rng.between(*min, *max),
+            Self::Counted { interval, .. } | Self::CountedOrUntil { interval, .. } => *interval,
+            Self::Checked { interval, .. } => *interval,
+            Self::Dynamic { delay, .. } | Self::SelfDirected { delay, .. } => *delay,
+            Self::Cron(_)
+            | Self::CronIn { .. }
+            | Self::DailyUtc { .. }
+            | Self::WeeklyUtc { .. }
+            | Self::Daily { .. }
+            | Self::Weekly { .. } => self
+                .next_occurrence(system_now)
+                .map_or(Duration::ZERO, |next| Self::until(next, system_now)),
+            Self::Then(first, _) => first.delay(now, system_now, rng),
+            Self::Never => FAR_FUTURE,
+        }
+    }
+
+    /// Computes the first execution relative to `now`.
+    ///
+    /// `system_now` must be sampled together with `now` so that wall-clock
+    /// based schedules can be mapped onto the monotonic timeline.
+    fn first_at(&self, now: Stbi, system_now: SystemTime, rng: &mut Rng) -> Stbi {
+        match self {
+            Self::OnceAt(instant) => Stbi(*instant),
+            Self::At(time) => now + Self::until(*time, system_now),
+            Self::EveryAligned(interval) => now + Self::until_aligned(*interval, system_now),
+            Self::EveryWithOffset { offset, .. } => now + *offset,
+            Self::Cron(_)
+            | Self::CronIn { .. }
+            | Self::DailyUtc { .. }
+            | Self::WeeklyUtc { .. }
+            | Self::Daily { .. }
+            | Self::Weekly { .. } => match self.next_occurrence(system_now) {
+                Some(next) => now + Self::until(next, system_now),
+                None => now + FAR_FUTURE,
+            },
+            Self::Then(first, _) => first.first_at(now, system_now, rng),
+            _ => now + self.next_interval(now, system_now, rng),
+        }
+    }
+
+    /// The delay until the next execution, with any randomness applied.
+    fn next_interval(&self, now: Stbi, system_now: SystemTime, rng: &mut Rng) -> Duration {
+        match self {
+            Self::EveryJittered { interval, jitter } => rng.jitter(*interval, *jitter),
+            Self::Then(first, _) => first.next_interval(now, system_now, rng),
+            _ => self.delay(now, system_now, rng),
+        }
+    }
+
+    /// Raises the interval of recurring schedules to at least `min`.
+    fn clamp_interval(&mut self, min: Duration) {
+        match self {
+            Self::Every(interval)
+            | Self::FixedRate(interval)
+            | Self::FixedDelay(interval)
+            | Self::EveryAligned(interval)
+            | Self::EveryWithOffset { interval, .. }
+            | Self::EveryJittered { interval, .. }
+            | Self::Counted { interval, .. }
+            | Self::CountedOrUntil { interval, .. }
+            | Self::Checked { interval, .. } => *interval = (*interval).max(min),
+            Self::RandomInterval {
+                min: lower,
+                max: upper,
+            } => {
+                *lower = (*lower).max(min);
+                *upper = (*upper).max(min);
+            }
+            Self::Then(first, second) => {
+                first.clamp_interval(min);
+                second.clamp_interval(min);
+            }
+            Self::Once(_)
+            | Self::OnceAt(_)
+            | Self::At(_)
+            | Self::Dynamic { .. }
+            | Self::SelfDirected { .. }
+            | Self::Cron(_)
+            | Self::CronIn { .. }
+            | Self::DailyUtc { .. }
+            | Self::WeeklyUtc { .. }
+            | Self::Daily { .. }
+            | Self::Weekly { .. }
+            | Self::Never => {}
+        }
+    }
+
+    /// The fixed interval of recurring schedules.
+    fn interval(&self) -> Option<Duration> {
+        match self {
+            Self::Every(interval)
+            | Self::FixedRate(interval)
+            | Self::FixedDelay(interval)
+            | Self::EveryAligned(interval)
+            | Self::EveryWithOffset { interval, .. }
+            | Self::EveryJittered { interval, .. }
+            | Self::Counted { interval, .. }
+            | Self::CountedOrUntil { interval, .. }
+            | Self::Checked { interval, .. } => Some(*interval),
+            Self::Then(first, _) => first.interval(),
+            Self::Once(_)
+            | Self::OnceAt(_)
+            | Self::At(_)
+            | Self::RandomInterval { .. }
+            | Self::Dynamic { .. }
+            | Self::SelfDirected { .. }
+            | Self::Cron(_)
+            | Self::CronIn { .. }
+            | Self::DailyUtc { .. }
+            | Self::WeeklyUtc { .. }
+            | Self::Daily { .. }
+            | Self::Weekly { .. }
+            | Self::Never => None,
+        }
+    }
+
+    /// Whether the schedule is defined by the wall-clock rather than by
+    /// intervals on the monotonic clock.
+    fn is_wall_clock(&self) -> bool {
+        matches!(self.current(), Self::At(_)) || self.is_calendar()
+    }
+
+    /// Whether the schedule recurs at wall-clock times of the calendar.
+    fn is_calendar(&self) -> bool {
+        matches!(
+            self.current(),
+            Self::Cron(_)
+                | Self::CronIn { .. }
+                | Self::DailyUtc { .. }
+                | Self::WeeklyUtc { .. }
+                | Self::Daily { .. }
+                | Self::Weekly { .. }
+        )
+    }
+
+    /// The instant after which the schedule in effect stops.
+    fn deadline(&self) -> Option<Stbi> {
+        match self.current() {
+            Self::CountedOrUntil { until, .. } => Some(*until),
+            _ => None,
+        }
+    }
+
+    /// The schedule in effect, i.e. the current stage of a [`Schedule::Then`].
+    fn current(&self) -> &Self {
+        match self {
+            Self::Then(first, _) => first.current(),
+            _ => self,
+        }
+    }
+
+    /// The number of schedules which are followed one after another.
+    fn stages(&self) -> usize {
+        match self {
+            Self::Then(first, second) => first.stages() + second.stages(),
+            _ => 1,
+        }
+    }
+
+    /// Whether the schedule will never execute its task.
+    fn is_exhausted(&self, system_now: SystemTime) -> bool {
+        if let Self::Then(first, second) = self {
+            first.is_exhausted(system_now) && second.is_exhausted(system_now)
+        } else if self.is_calendar() {
+            self.next_occurrence(system_now).is_none()
+        } else {
+            self.remaining() == Some(0)
+        }
+    }
+
+    /// The first occurrence of a calendar schedule after `after`.
+    fn next_occurrence(&self, after: SystemTime) -> Option<SystemTime> {
+        match self.current() {
+            Self::Cron(expression) => Cron::parse(expression).ok()?.next_after(after),
+            Self::DailyUtc { at } => Some(calendar::next_daily(*at, after)),
+            Self::WeeklyUtc { weekday, at } => Some(calendar::next_weekly(*weekday, *at, after)),
+            Self::CronIn { expression, zone } => {
+                let cron = Cron::parse(expression).ok()?;
+                calendar::next_in_zone(after, zone.as_ref(), |local| cron.next_after(local))
+            }
+            Self::Daily { at, zone } => calendar::next_in_zone(after, zone.as_ref(), |local| {
+                Some(calendar::next_daily(*at, local))
+            }),
+            Self::Weekly { weekday, at, zone } => {
+                calendar::next_in_zone(after, zone.as_ref(), |local| {
+                    Some(calendar::next_weekly(*weekday, *at, local))
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn until(time: SystemTime, system_now: SystemTime) -> Duration {
+        time.duration_since(system_now).unwrap_or(Duration::ZERO)
+    }
+
+    /// The delay until the next multiple of `interval` since the Unix epoch,
+    /// or zero if `system_now` lies exactly on one.
+    fn until_aligned(interval: Duration, system_now: SystemTime) -> Duration {
+        let elapsed = system_now
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_nanos();
+
+        match elapsed.checked_rem(interval.as_nanos()) {
+            Some(0) | None => Duration::ZERO,
+            Some(offset) => duration_from_nanos(interval.as_nanos() - offset),
         }
     }
 
-    pub fn with<F>(self, f: F) -> Task
+    pub fn with<'a, F>(self, f: F) -> Task<'a>
     where
-        F: 'static + FnMut(),
+        F: 'a + Send + FnMut(),
     {
-        Task {
-            schedule: self,
-            f: Box::new(f),
-        }
+        self.with_boxed(Box::new(f))
     }
 
-    pub fn with_boxed(self, f: TaskFunction) -> Task {
-        Task { schedule: self, f }
+    /// Creates a task whose executions may fail.
+    ///
+    /// Failed executions are retried according to [`Task::retry`]. Errors
+    /// which are not retried are passed to the handler set by
+    /// [`Scheduler::with_error_handler`].
+    pub fn with_result<'a, F, E>(self, mut f: F) -> Task<'a>
+    where
+        F: 'a + Send + FnMut() -> Result<(), E>,
+        E: Into<TaskError>,
+    {
+        let f: Box<dyn FnMut() -> Result<(), TaskError> + Send + 'a> =
+            Box::new(move || f().map_err(Into::into));
+        Task::new(self, Job::Fallible(f))
     }
-}
 
-pub type TaskFunction = Box<dyn FnMut() + 'static>;
+    pub fn with_boxed(self, f: TaskFunction<'_>) -> Task<'_> {
+        Task::new(self, Job::Repeating(f))
+    }
 
-pub struct Task {
-    schedule: Schedule,
-    f: TaskFunction,
+    /// Creates a task which executes `f` at most once.
+    ///
+    /// The task is dropped after its first execution, even if the schedule
+    /// would recur.
+    pub fn with_once<'a, F>(self, f: F) -> Task<'a>
+    where
+        F: 'a + Send + FnOnce(),
+    {
+        let f: Box<dyn FnOnce() + Send + 'a> = Box::new(f);
+        Task::new(self, Job::Once(Some(f)))
+    }
 }
 
-pub struct ScheduledTask {
-    at: Stbi,
-    task: Task,
+/// The function of a task, which may borrow data living for `'a`.
+///
+/// All functions held by a scheduler are `Send`, so that it can be moved onto
+/// its own thread (see [`Scheduler::spawn`]). Spawning and worker threads
+/// require `'static` functions. The task functions of a [`LocalScheduler`]
+/// need not be `Send`, see [`Schedule::with_local`].
+pub type TaskFunction<'a> = Box<dyn FnMut() + Send + 'a>;
+
+/// The error of a failed task execution, see [`Schedule::with_result`].
+pub type TaskError = Box<dyn Error + Send + Sync + 'static>;
+
+enum Job<'a, T: Threading = Concurrent> {
+    Repeating(Box<T::Function<'a>>),
+    Fallible(Box<T::Fallible<'a>>),
+    Once(Option<Box<T::Once<'a>>>),
+    /// A repeating function which was handed to a worker thread.
+    Shared(Arc<Mutex<Job<'a, T>>>),
+    /// A function returning the delay until its next execution, see
+    /// [`Schedule::self_directed`], and the delay its last execution
+    /// returned until the scheduler takes it.
+    Directed {
+        f: DirectedFunction<'a>,
+        returned: Option<Option<Duration>>,
+    },
+    #[cfg(feature = "tokio")]
+    Async(AsyncTaskFunction),
 }
 
-impl ScheduledTask {
-    pub fn reschedule(mut self, stbi: Stbi) -> Option<Self> {
-        let schedule = self.task.schedule.reschedule()?;
-        self.task.schedule = schedule;
-        self.at = stbi + *self.task.schedule.as_duration();
-        Some(self)
+impl<'a, T: Threading> Job<'a, T> {
+    fn call(&mut self) -> Result<(), TaskError> {
+        match self {
+            Self::Repeating(f) => f(),
+            Self::Fallible(f) => return f(),
+            Self::Once(f) => {
+                if let Some(f) = f.take() {
+                    f()
+                }
+            }
+            Self::Shared(job) => return job.lock().unwrap_or_else(PoisonError::into_inner).call(),
+            Self::Directed { f, returned } => *returned = Some(f()),
+            #[cfg(feature = "tokio")]
+            Self::Async(f) => drop(tokio::runtime::Handle::try_current()?.spawn(f())),
+        }
+
+        Ok(())
     }
-}
 
-impl PartialEq for ScheduledTask {
-    fn eq(&self, other: &Self) -> bool {
-        self.at == other.at
+    fn is_spent(&self) -> bool {
+        matches!(self, Self::Once(None))
+    }
+
+    /// Hands out the function to be executed elsewhere.
+    ///
+    /// Repeating functions are moved behind a shared lock on their first
+    /// call, so that the scheduler keeps them for the next execution. Once
+    /// functions are taken instead, returning `None` when already spent.
+    fn share(&mut self) -> Option<Arc<Mutex<Job<'a, T>>>> {
+        match self {
+            Self::Shared(shared) => Some(Arc::clone(shared)),
+            Self::Once(f) => Some(Arc::new(Mutex::new(Self::Once(Some(f.take()?))))),
+            _ => {
+                let shared = Arc::new(Mutex::new(std::mem::replace(self, Self::Once(None))));
+                *self = Self::Shared(Arc::clone(&shared));
+                Some(shared)
+            }
+        }
     }
 }
 
-impl Eq for ScheduledTask {}
+pub struct Task<'a, T: Threading = Concurrent> {
+    schedule: Schedule,
+    f: Job<'a, T>,
+    priority: i32,
+    retry: RetryPolicy,
+    missed_ticks: Option<MissedTickBehavior>,
+    immediate: bool,
+    name: Option<Arc<str>>,
+    group: Option<Arc<str>>,
+    timeout: Option<Duration>,
+    overlap: Option<OverlapPolicy>,
+    condition: Option<Condition<'a>>,
+}
 
-impl PartialOrd for ScheduledTask {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.at.partial_cmp(&other.at)
+impl<'a, T: Threading> Task<'a, T> {
+    fn new(schedule: Schedule, f: Job<'a, T>) -> Self {
+        Self {
+            schedule,
+            f,
+            priority: 0,
+            retry: RetryPolicy::Never,
+            missed_ticks: None,
+            immediate: false,
+            name: None,
+            group: None,
+            timeout: None,
+            overlap: None,
+            condition: None,
+        }
+    }
+
+    /// Labels the task for debugging, e.g. in its [`TaskStats`] and in log
+    /// events.
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(Arc::from(name.into()));
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Adds the task to a group, which can be cancelled at once by
+    /// [`Scheduler::cancel_group`].
+    pub fn in_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(Arc::from(group.into()));
+        self
+    }
+
+    pub fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Sets the priority deciding which of several tasks due at the same time
+    /// runs first, higher priorities running earlier. Defaults to zero.
+    ///
+    /// Tasks of equal priority run in the order they were added.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Executes the task function once, e.g. for a task returned by
+    /// [`Scheduler::drain_due`].
+    ///
+    /// Panics of the function are not caught.
+    pub fn call(&mut self) -> Result<(), TaskError> {
+        self.f.call()
+    }
+
+    /// Sets how failed executions of a task created by
+    /// [`Schedule::with_result`] are retried. Defaults to
+    /// [`RetryPolicy::Never`].
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Executes the task right away when it is added, the following
+    /// executions continuing on its schedule from then.
+    pub fn fire_immediately(mut self) -> Self {
+        self.immediate = true;
+        self
+    }
+
+    /// Overrides how the next execution is computed once executions of a
+    /// recurring task were missed.
+    ///
+    /// By default [`Schedule::FixedRate`] bursts, [`Schedule::FixedDelay`]
+    /// always waits its interval after an execution and the other recurring
+    /// schedules skip. Calendar schedules such as [`Schedule::Cron`] replay
+    /// each missed occurrence on [`MissedTickBehavior::Burst`] and skip
+    /// otherwise. See also [`Scheduler::with_catch_up`].
+    pub fn missed_ticks(mut self, behavior: MissedTickBehavior) -> Self {
+        self.missed_ticks = Some(behavior);
+        self
+    }
+
+    /// Sets how long an execution on a worker (see
+    /// [`Scheduler::with_workers`]) may take before the task is considered
+    /// stuck.
+    ///
+    /// The time is measured on the clock of the scheduler from the dispatch,
+    /// including the wait for a free worker. A stuck task is reported to
+    /// [`Scheduler::on_timeout`] and no longer rescheduled. Its execution is
+    /// not terminated and keeps occupying its worker thread until it returns.
+    /// Dropping the scheduler waits for the executions on its workers, except
+    /// for stuck ones, whose threads are detached. Overlapping executions are
+    /// watched separately. Tasks executed inline are not watched.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Keeps executions on workers (see [`Scheduler::with_workers`]) from
+    /// piling up while the previous execution of the task is still running.
+    ///
+    /// By default such an execution is dispatched as well and waits for the
+    /// previous one to complete. Tasks executed inline never overlap.
+    pub fn no_overlap(mut self, policy: OverlapPolicy) -> Self {
+        self.overlap = Some(policy);
+        self
+    }
+
+    /// Only executes the task while `condition` holds, e.g. to flush a
+    /// buffer every second but only if it is not empty.
+    ///
+    /// The condition is checked on the thread running the scheduler whenever
+    /// the task is due, so it should return quickly. If it does not hold the
+    /// execution is skipped and the task continues on its schedule, the
+    /// skipped execution counting towards it like for a
+    /// [`Schedule::Counted`] task.
+    pub fn when<F>(mut self, condition: F) -> Self
+    where
+        F: 'a + Send + FnMut() -> bool,
+    {
+        self.condition = Some(Box::new(condition));
+        self
     }
 }
 
-impl Ord for ScheduledTask {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.at.cmp(&other.at)
+impl<T: Threading> fmt::Debug for Task<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Task")
+            .field("name", &self.name)
+            .field("group", &self.group)
+            .field("schedule", &self.schedule)
+            .field("priority", &self.priority)
+            .field("retry", &self.retry)
+            .field("missed_ticks", &self.missed_ticks)
+            .field("immediate", &self.immediate)
+            .field("timeout", &self.timeout)
+            .field("overlap", &self.overlap)
+            .finish_non_exhaustive()
     }
 }
 
-pub struct Scheduler {
-    schedule: BinaryHeap<ScheduledTask>,
+/// An opaque handle identifying a task within its [`Scheduler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TaskId(u64);
+
+pub struct ScheduledTask<'a, T: Threading = Concurrent> {
+    id: TaskId,
+    at: Stbi,
+    /// The wall-clock time `at` was mapped from for wall-clock schedules.
+    wall: Option<SystemTime>,
+    /// The insertion order, which breaks ties between tasks due at the same
+    /// time.
+    seq: u64,
+    task: Task<'a, T>,
+    rng: Rng,
+    retry: Option<Retry>,
+    /// The `at` of an execution deferred by the rate limit, onto which the
+    /// regular schedule continues.
+    deferred: Option<Stbi>,
+}
+
+/// The progress of retrying a failed execution.
+struct Retry {
+    attempts: u32,
+    /// The `at` of the execution which failed, onto which the regular
+    /// schedule continues.
+    scheduled: Stbi,
 }
 
-impl Scheduler {
-    pub fn with_tasks(tasks: Vec<Task>) -> Self {
-        let mut schedule = BinaryHeap::new();
+/// The times of an execution a [`ScheduledTask`] is rescheduled from, see
+/// [`ScheduledTask::reschedule_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RescheduleContext {
+    /// The time the execution was scheduled for, onto which the regular
+    /// schedule continues.
+    pub scheduled_at: Stbi,
+    /// The time the execution started.
+    pub fired_at: Stbi,
+    /// The time the execution completed.
+    pub completed_at: Stbi,
+    /// The wall-clock time at the completion, which wall-clock schedules
+    /// continue from.
+    pub system_completed_at: SystemTime,
+}
 
-        let now = Stbi::now();
+impl<'a, T: Threading> ScheduledTask<'a, T> {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
 
-        for task in tasks {
-            let at = now + *task.schedule.as_duration();
-            let task = ScheduledTask { at, task };
-            schedule.push(task)
+    pub fn task(&self) -> &Task<'a, T> {
+        &self.task
+    }
+
+    /// The time of the next execution.
+    pub fn at(&self) -> Stbi {
+        self.at
+    }
+
+    /// The task with its remaining schedule, without its timing.
+    pub fn into_task(self) -> Task<'a, T> {
+        self.task
+    }
+
+    fn info(&self) -> NextInfo {
+        NextInfo {
+            id: self.id,
+            name: self.task.name.clone(),
+            at: self.at,
+            kind: self.task.schedule.kind(),
+        }
+    }
+
+    /// Moves the task, including the time its schedule continues from, later
+    /// by `offset`.
+    fn shift(&mut self, offset: Duration) {
+        self.at = self.at + offset;
+
+        if let Some(retry) = &mut self.retry {
+            retry.scheduled = retry.scheduled + offset;
         }
 
-        Self { schedule }
+        if let Some(deferred) = &mut self.deferred {
+            *deferred = *deferred + offset;
+        }
     }
 
-    pub fn run(mut self) {
-        loop {
-            let now = Stbi::now();
+    /// Moves the task to its next retry after a failed execution at `now`.
+    ///
+    /// Returns `false` and leaves the task unchanged if it is out of retries.
+    fn retry(&mut self, now: Stbi) -> bool {
+        let attempts = self.retry.as_ref().map_or(0, |retry| retry.attempts);
 
-            let Some(top) = self.schedule.peek() else {
-                return;
-            };
+        let Some(delay) = self.task.retry.delay(attempts) else {
+            return false;
+        };
+
+        let deferred = self.deferred.take();
+        let scheduled = self
+            .retry
+            .take()
+            .map_or(deferred.unwrap_or(self.at), |retry| retry.scheduled);
+        self.retry = Some(Retry {
+            attempts: attempts + 1,
+            scheduled,
+        });
+        self.at = now + delay;
 
-            let diff = top.at.since(now);
+        true
+    }
 
-            if diff.is_zero() {
-                // We are past the `at` timestamp
-                let mut task = self.schedule.pop().expect("Peek returned value");
+    /// Computes the next execution of this task, `now` being the time the
+    /// last execution completed.
+    ///
+    /// The next `at` is anchored to the previous `at` rather than to the time
+    /// the task finished, so long running tasks do not push back every
+    /// following execution. If the next execution would already lie in the
+    /// past (relative to `now`), it is advanced in whole intervals until it is
+    /// not. [`Schedule::FixedRate`] and [`Schedule::FixedDelay`] deviate from
+    /// this as documented on them. Wall-clock schedules continue from the
+    /// current system time.
+    pub fn reschedule(self, now: Stbi) -> Option<Self> {
+        let context = self.context(now, now, SystemTime::now());
+        self.reschedule_with(context)
+    }
 
-                (task.task.f)();
+    /// The context of an execution which started at `fired_at` and completed
+    /// at `completed_at`.
+    ///
+    /// The execution was scheduled for the `at` of the task, unless the rate
+    /// limit deferred it or it is being retried.
+    fn context(
+        &self,
+        fired_at: Stbi,
+        completed_at: Stbi,
+        system_completed_at: SystemTime,
+    ) -> RescheduleContext {
+        let scheduled_at = match &self.retry {
+            Some(retry) => retry.scheduled,
+            None => self.deferred.unwrap_or(self.at),
+        };
 
-                // Push next execution
-                if let Some(task) = task.reschedule(now) {
-                    self.schedule.push(task);
-                }
-            } else {
-                std::thread::sleep(diff);
-            }
+        RescheduleContext {
+            scheduled_at,
+            fired_at,
+            completed_at,
+            system_completed_at,
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::println;
+    /// Like [`ScheduledTask::reschedule`], but with the times of the
+    /// execution given separately.
+    pub fn reschedule_with(mut self, context: RescheduleContext) -> Option<Self> {
+        if self.task.f.is_spent() {
+            return None;
+        }
 
-    use super::*;
+        let now = context.completed_at;
+        let system_now = context.system_completed_at;
+        self.at = context.scheduled_at;
+        self.deferred = None;
+        self.retry = None;
 
-    #[test]
-    fn simple_schedule() {
-        let tasks = vec![
-            Schedule::Once(Some(Duration::from_secs(5))).with(|| {
-                println!("Delayed hello world");
-            }),
-            Schedule::Once(None).with(|| {
-                println!("Instant hello world");
-            }),
-            Schedule::Every(Duration::from_millis(125)).with(|| {
-                println!("I am annoying");
+        let stages = self.task.schedule.stages();
+        let schedule = self.task.schedule.reschedule_after(system_now)?;
+        self.task.schedule = schedule;
+
+        if self.task.schedule.stages() < stages {
+            // The next stage of a `Schedule::Then` starts from scratch.
+            let schedule = &self.task.schedule;
+            self.at = schedule.first_at(now, system_now, &mut self.rng);
+            self.wall = schedule
+                .is_wall_clock()
+                .then(|| system_now + self.at.since(now));
+            return Some(self);
+        }
+
+        let schedule = self.task.schedule.current();
+
+        if schedule.is_calendar() {
+            let replay = self.task.missed_ticks == Some(MissedTickBehavior::Burst);
+            let (at, wall) = Self::next_calendar(schedule, self.wall, replay, now, system_now)?;
+            self.at = at;
+            self.wall = Some(wall);
+            return Some(self);
+        }
+
+        let interval = self
+            .task
+            .schedule
+            .next_interval(now, system_now, &mut self.rng);
+
+        self.at = match (self.task.schedule.current(), self.task.missed_ticks) {
+            (_, Some(MissedTickBehavior::Burst)) | (Schedule::FixedRate(_), None) => {
+                self.at + interval
+            }
+            (_, Some(MissedTickBehavior::Delay)) => {
+                let next = self.at + interval;
+
+                if now.since(next).is_zero() {
+                    next
+                } else {
+                    now + interval
+                }
+            }
+            (Schedule::FixedDelay(_), None) => now + interval,
+            _ => Self::next_on_grid(self.at, interval, now),
+        };
+
+        if let Some(until) = self.task.schedule.deadline() {
+            if self
+                .at
+                .checked_since(until)
+                .is_some_and(|late| !late.is_zero())
+            {
+                return None;
+            }
+        }
+
+        Some(self)
+    }
+
+    /// The next occurrence of a calendar schedule after the `previous` one,
+    /// together with its wall-clock time.
+    ///
+    /// Occurrences which already passed are skipped unless they are
+    /// `replay`ed. If the previous occurrence lies in the future, the clock
+    /// was set back and the search starts from `system_now` instead of
+    /// waiting for the clock to catch up.
+    fn next_calendar(
+        schedule: &Schedule,
+        previous: Option<SystemTime>,
+        replay: bool,
+        now: Stbi,
+        system_now: SystemTime,
+    ) -> Option<(Stbi, SystemTime)> {
+        let after = match previous {
+            Some(previous) if replay && previous <= system_now => previous,
+            Some(previous) if previous <= system_now + WALL_CLOCK_TOLERANCE => {
+                previous.max(system_now)
+            }
+            _ => system_now,
+        };
+
+        let next = schedule.next_occurrence(after)?;
+
+        Some((now + Schedule::until(next, system_now), next))
+    }
+
+    fn next_on_grid(previous: Stbi, interval: Duration, now: Stbi) -> Stbi {
+        if interval.is_zero() {
+            return now;
+        }
+
+        let next = previous + interval;
+        let behind = now.since(next);
+
+        if behind.is_zero() {
+            return next;
+        }
+
+        let interval_nanos = interval.as_nanos();
+        let skip_nanos = behind.as_nanos().div_ceil(interval_nanos) * interval_nanos;
+
+        next + duration_from_nanos(skip_nanos)
+    }
+}
+
+fn duration_from_nanos(nanos: u128) -> Duration {
+    const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+    Duration::new(
+        (nanos / NANOS_PER_SEC) as u64,
+        (nanos % NANOS_PER_SEC) as u32,
+    )
+}
+
+impl<T: Threading> ScheduledTask<'_, T> {
+    fn label(&self) -> Label<'_> {
+        Label(self.id, self.task.name())
+    }
+
+    /// How the task continues after missed executions, see
+    /// [`Task::missed_ticks`].
+    fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.task
+            .missed_ticks
+            .unwrap_or(match self.task.schedule.current() {
+                Schedule::FixedRate(_) => MissedTickBehavior::Burst,
+                Schedule::FixedDelay(_) => MissedTickBehavior::Delay,
+                _ => MissedTickBehavior::Skip,
+            })
+    }
+}
+
+impl<T: Threading> fmt::Debug for ScheduledTask<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScheduledTask")
+            .field("id", &self.id)
+            .field("at", &self.at)
+            .field("task", &self.task)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifies a task in log events by its id and name.
+struct Label<'n>(TaskId, Option<&'n str>);
+
+impl fmt::Display for Label<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(name) => write!(f, "{:?} ({name})", self.0),
+            None => write!(f, "{:?}", self.0),
+        }
+    }
+}
+
+impl<T: Threading> PartialEq for ScheduledTask<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl<T: Threading> Eq for ScheduledTask<'_, T> {}
+
+impl<T: Threading> PartialOrd for ScheduledTask<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Threading> Ord for ScheduledTask<'_, T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // The heap pops the greatest task first, so higher priorities and
+        // earlier insertions compare greater.
+        self.at
+            .cmp(&other.at)
+            .then_with(|| self.task.priority.cmp(&other.task.priority))
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// What became of the next task when the scheduler tried to execute it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fired {
+    /// The task was executed or dispatched to the workers.
+    Ran,
+    /// The execution was skipped by the condition of the task or
+    /// [`OverlapPolicy::Skip`].
+    Skipped,
+    /// The execution was postponed by the rate limit or
+    /// [`OverlapPolicy::Delay`].
+    Held,
+}
+
+/// A task popped from the queue together with the result of its execution.
+type Executed<'a, T> = (ScheduledTask<'a, T>, Result<(), TaskError>);
+
+/// The next task as prepared by [`Scheduler::begin_next`].
+enum Next<'a, T: Threading> {
+    /// The task is to be executed by the caller, it is late by the duration.
+    Execute(ScheduledTask<'a, T>, Duration),
+    /// The task was handled without the caller.
+    Done(Fired, Option<Executed<'a, T>>),
+}
+
+/// The result of [`Scheduler::tick`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickOutcome {
+    /// The number of tasks which were executed.
+    pub ran: usize,
+    /// The time from the tick until the next task is due, or `None` if no
+    /// tasks are left.
+    pub next: Option<Duration>,
+}
+
+/// What a run of [`Scheduler::run`], [`Scheduler::run_until`] or
+/// [`Scheduler::run_n`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunSummary {
+    /// The number of tasks which were executed, including those dispatched
+    /// to workers.
+    pub fired: usize,
+    /// The number of tasks which were dropped before completing their
+    /// schedule, see [`Recorder::record_drop`].
+    pub dropped: usize,
+    /// How long the run took according to the clock.
+    pub elapsed: Duration,
+}
+
+/// A scheduled task and its next execution, see [`Scheduler::peek_next`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextInfo {
+    pub id: TaskId,
+    pub name: Option<Arc<str>>,
+    pub at: Stbi,
+    /// The kind of its schedule, see [`Schedule::kind`].
+    pub kind: ScheduleKind,
+}
+
+/// How a recurring task continues after executions were missed, e.g.
+/// because the scheduler was blocked by a long running task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Catches up on the missed executions back-to-back.
+    Burst,
+    /// Executes once and continues with the next execution on the original
+    /// grid which lies in the future.
+    Skip,
+    /// Executes once and continues on a new grid starting from then.
+    Delay,
+}
+
+/// How a scheduler handles executions of recurring tasks which are far
+/// behind, e.g. after the scheduler was blocked, suspended or restored from a
+/// saved schedule; see [`Scheduler::with_catch_up`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatchUpPolicy {
+    /// Coalesces the missed executions into one, continuing with the next
+    /// execution which lies in the future ([`MissedTickBehavior::Skip`]).
+    Realtime,
+    /// Replays every missed execution in order, back-to-back
+    /// ([`MissedTickBehavior::Burst`]).
+    CatchUp,
+}
+
+impl CatchUpPolicy {
+    fn missed_ticks(self) -> MissedTickBehavior {
+        match self {
+            Self::Realtime => MissedTickBehavior::Skip,
+            Self::CatchUp => MissedTickBehavior::Burst,
+        }
+    }
+}
+
+/// What a running scheduler does once no tasks are left, see
+/// [`Scheduler::with_empty_behavior`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyBehavior {
+    /// Returns from the run loop, which suits a fixed set of tasks.
+    #[default]
+    Exit,
+    /// Keeps running until tasks are added or the scheduler is shut down,
+    /// which suits a long-lived scheduler receiving tasks while it runs.
+    Block,
+}
+
+/// What happens to an execution which becomes due while the previous one is
+/// still running, see [`Task::no_overlap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapPolicy {
+    /// Skips the execution, continuing with the next one on the schedule as
+    /// if it had been dispatched.
+    Skip,
+    /// Holds the execution until the previous one completed, then dispatches
+    /// it. The schedule continues from the time it was due.
+    Delay,
+}
+
+/// Decides what happens to a task whose function panicked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanicAction {
+    /// Drops the task, including all future executions.
+    Drop,
+    /// Keeps the task on its schedule as if it returned normally.
+    Reschedule,
+    /// Resumes the panic, unwinding out of [`Scheduler::run`].
+    Propagate,
+}
+
+pub type PanicHandler = Box<dyn FnMut(TaskId, &(dyn Any + Send)) -> PanicAction + Send + 'static>;
+
+/// Receives the errors of failed executions which are not retried, see
+/// [`Scheduler::with_error_handler`].
+pub type ErrorHandler = Box<dyn FnMut(TaskId, &TaskError) + Send + 'static>;
+
+/// Invoked right before a task executes, see [`Scheduler::on_before`].
+pub type BeforeCallback = Box<dyn FnMut(&TaskId) + Send + 'static>;
+
+/// Invoked right after a task executed with the duration of the execution,
+/// see [`Scheduler::on_after`].
+pub type AfterCallback = Box<dyn FnMut(&TaskId, Duration) + Send + 'static>;
+
+/// Invoked with the duration of an execution which took longer than the
+/// interval of its task, see [`Scheduler::on_overrun`].
+pub type OverrunCallback = Box<dyn FnMut(&TaskId, Duration, Duration) + Send + 'static>;
+
+/// Decides whether a due task executes, see [`Task::when`].
+type Condition<'a> = Box<dyn FnMut() -> bool + Send + 'a>;
+
+/// Invoked with the time a stuck execution ran so far, see
+/// [`Scheduler::on_timeout`].
+pub type TimeoutCallback = Box<dyn FnMut(&TaskId, Duration) + Send + 'static>;
+
+/// The default lower bound for intervals of recurring tasks.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The default for the longest a scheduler sleeps at once, see
+/// [`Scheduler::with_sleep_slice`].
+pub const DEFAULT_SLEEP_SLICE: Duration = Duration::from_millis(250);
+
+/// The default for how late an execution may start before it counts as
+/// overdue, see [`Scheduler::with_grace_period`].
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_millis(10);
+
+/// How often a scheduler checks for a free worker while it holds due tasks,
+/// see [`Scheduler::with_max_in_flight`].
+const IN_FLIGHT_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// The longest a scheduler sleeps before checking its [`ShutdownToken`].
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The longest time [`Scheduler::run_pending`] reports until the next task.
+///
+/// Wall-clock tasks ([`Schedule::At`] and the calendar schedules such as
+/// [`Schedule::Cron`]) keep their wall-clock time and are mapped onto the
+/// monotonic clock, which stops being accurate once the system clock is
+/// adjusted (e.g. by NTP or after a suspend). Bounding each sleep makes the
+/// scheduler notice such a jump within this time: it then maps the pending
+/// wall-clock tasks again, so it neither sleeps for hours after the clock was
+/// set back nor waits for occurrences which already passed. Smaller
+/// adjustments are followed by mapping the next task again whenever the
+/// scheduler wakes up.
+///
+/// A wall-clock task therefore executes at its wall-clock time up to the
+/// time between reading both clocks, typically microseconds, plus any
+/// adjustment of the system clock while the scheduler sleeps towards it,
+/// which is bounded by the drift of the clocks over one slice.
+pub const WALL_CLOCK_SLICE: Duration = Duration::from_secs(60);
+
+/// Deviations between the system clock and the monotonic clock up to this
+/// are treated as drift rather than a jump.
+const WALL_CLOCK_TOLERANCE: Duration = Duration::from_secs(1);
+
+/// Schedules tasks whose functions live for `'a`, see [`TaskFunction`].
+///
+/// The task functions are `Send` unless the scheduler is a
+/// [`LocalScheduler`], see [`Threading`].
+pub struct Scheduler<'a, C = SystemClock, T: Threading = Concurrent> {
+    schedule: Queue<'a, T>,
+    /// Tasks which were cancelled but are still part of `schedule`.
+    cancelled: HashSet<TaskId>,
+    next_id: u64,
+    next_seq: u64,
+    clock: C,
+    panic_handler: Option<PanicHandler>,
+    error_handler: Option<ErrorHandler>,
+    on_before: Option<BeforeCallback>,
+    on_after: Option<AfterCallback>,
+    on_overrun: Option<OverrunCallback>,
+    on_timeout: Option<TimeoutCallback>,
+    recorder: Option<Box<dyn Recorder>>,
+    /// Woken when an added task is due before the previously next one.
+    waker: Option<Waker>,
+    state: StateHandle,
+    stats: HashMap<TaskId, TaskStats>,
+    /// The scheduled tasks of each group.
+    groups: HashMap<Arc<str>, HashSet<TaskId>>,
+    /// Tasks taken out of `schedule` by [`Scheduler::pause`].
+    paused: HashMap<TaskId, ScheduledTask<'a, T>>,
+    /// When [`Scheduler::pause_all`] froze the timing.
+    paused_since: Option<Stbi>,
+    catch_up: Option<CatchUpPolicy>,
+    /// Tasks waiting for the first execution of another task with the delay
+    /// after it, see [`Scheduler::add_after`].
+    dependents: HashMap<TaskId, Vec<(TaskId, Duration, Task<'a, T>)>>,
+    min_interval: Duration,
+    sleep_slice: Duration,
+    /// Waits shorter than this are spun instead of slept.
+    spin_threshold: Option<Duration>,
+    grace_period: Duration,
+    pool: Option<Pool<'a, T>>,
+    max_in_flight: Option<usize>,
+    rate_limit: Option<RateLimit>,
+    /// Simultaneous readings of both clocks from when wall-clock tasks were
+    /// last mapped onto the monotonic clock.
+    wall_anchor: (Stbi, SystemTime),
+    /// Seeds the generators of the tasks, see [`Scheduler::with_seed`].
+    rng: Rng,
+    /// The number of tasks dropped so far, see [`RunSummary::dropped`].
+    dropped: usize,
+    empty_behavior: EmptyBehavior,
+}
+
+impl<'a> Scheduler<'a> {
+    pub fn new() -> Self {
+        Self::with_tasks(Vec::new())
+    }
+
+    pub fn with_tasks(tasks: Vec<Task<'a>>) -> Self {
+        Self::with_clock(SystemClock, tasks)
+    }
+
+    /// Creates a scheduler with space for at least `capacity` tasks, see
+    /// [`Scheduler::reserve`].
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut scheduler = Self::new();
+        scheduler.reserve(capacity);
+        scheduler
+    }
+
+    /// Like [`Scheduler::with_tasks`], but schedules the tasks relative to
+    /// `epoch` instead of now, see [`Scheduler::with_clock_at`].
+    pub fn with_tasks_at(tasks: Vec<Task<'a>>, epoch: Stbi) -> Self {
+        Self::with_clock_at(SystemClock, tasks, epoch)
+    }
+}
+
+impl Default for Scheduler<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> FromIterator<Task<'a>> for Scheduler<'a> {
+    /// Schedules the tasks relative to the same instant, like
+    /// [`Scheduler::with_tasks`].
+    fn from_iter<I: IntoIterator<Item = Task<'a>>>(tasks: I) -> Self {
+        let mut scheduler = Self::new();
+        scheduler.extend(tasks);
+        scheduler
+    }
+}
+
+impl<C: Clock> Scheduler<'static, C> {
+    /// Executes tasks on a pool of `workers` threads instead of the thread
+    /// running the scheduler, which then only keeps track of time.
+    ///
+    /// Recurring tasks are rescheduled as soon as they are dispatched, not
+    /// once they completed. An execution which becomes due while the previous
+    /// one is still running waits for it to complete. Panics and errors are
+    /// caught by the workers and passed to the panic and error handlers the
+    /// next time the scheduler checks for due tasks; failed executions are
+    /// not retried.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.pool = Some(Pool::new(workers));
+        self
+    }
+
+    /// Dispatches at most `max` tasks to the workers at once.
+    ///
+    /// Due tasks beyond the limit stay scheduled at their time until a worker
+    /// completes one, so a burst of due tasks does not pile up in the queue
+    /// of the pool. The limit is raised to at least one and has no effect
+    /// without [`Scheduler::with_workers`].
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = Some(max.max(1));
+        self
+    }
+}
+
+impl<'a, C: Clock> Scheduler<'a, C> {
+    pub fn with_clock(clock: C, tasks: Vec<Task<'a>>) -> Self {
+        let now = clock.now();
+        Self::with_clock_at(clock, tasks, now)
+    }
+
+    /// Like [`Scheduler::with_clock`], but schedules the tasks relative to
+    /// `epoch` instead of now, as if they were added at `epoch`.
+    ///
+    /// This restores a schedule as if it started at an earlier time, e.g.
+    /// three seconds ago: an [`Schedule::Every`] task with an interval of one
+    /// second is then overdue and catches up on its missed executions
+    /// according to [`Task::missed_ticks`]. The wall-clock time of `epoch` is
+    /// derived from the current offset between the clocks.
+    pub fn with_clock_at(clock: C, tasks: Vec<Task<'a>>, epoch: Stbi) -> Self {
+        Self::build(clock, tasks, epoch)
+    }
+
+    /// Replaces the function of a scheduled or paused task with `f`, e.g. to
+    /// reload its logic, leaving its next execution and schedule untouched.
+    ///
+    /// The task keeps its other settings like [`Task::retry`], `f` executes
+    /// like a function given to [`Schedule::with`]. A
+    /// [`Schedule::SelfDirected`] task keeps the delay it was directed to
+    /// last. An execution already running on a worker finishes with the
+    /// previous function. Returns
+    /// `false` if the task is not (or no longer) scheduled.
+    pub fn replace_closure(&mut self, id: TaskId, f: TaskFunction<'a>) -> bool {
+        if self.cancelled.contains(&id) {
+            return false;
+        }
+
+        if let Some(task) = self.paused.get_mut(&id) {
+            task.direct();
+            task.task.f = Job::Repeating(f);
+            return true;
+        }
+
+        let Some(mut task) = self.schedule.remove(id) else {
+            return false;
+        };
+
+        trace!("Replaced the function of task {}", task.label());
+        task.direct();
+        task.task.f = Job::Repeating(f);
+        self.schedule.push(task);
+
+        true
+    }
+}
+
+impl<'a, C: Clock, T: Threading> Scheduler<'a, C, T> {
+    /// Creates a scheduler holding `tasks` scheduled relative to `epoch`.
+    fn build(clock: C, tasks: Vec<Task<'a, T>>, epoch: Stbi) -> Self {
+        let now = clock.now();
+        let system_now = clock.system_now();
+        let system_epoch = system_now
+            .checked_sub(now.since(epoch))
+            .and_then(|system_epoch| system_epoch.checked_add(epoch.since(now)))
+            .unwrap_or(system_now);
+        let min_interval = clock.min_interval();
+
+        let mut scheduler = Self {
+            schedule: Queue::default(),
+            cancelled: HashSet::new(),
+            next_id: 0,
+            next_seq: 0,
+            clock,
+            panic_handler: None,
+            error_handler: None,
+            on_before: None,
+            on_after: None,
+            on_overrun: None,
+            on_timeout: None,
+            recorder: None,
+            waker: None,
+            state: StateHandle::new(),
+            stats: HashMap::new(),
+            groups: HashMap::new(),
+            paused: HashMap::new(),
+            paused_since: None,
+            catch_up: None,
+            dependents: HashMap::new(),
+            min_interval,
+            sleep_slice: DEFAULT_SLEEP_SLICE,
+            spin_threshold: None,
+            grace_period: DEFAULT_GRACE_PERIOD,
+            pool: None,
+            max_in_flight: None,
+            rate_limit: None,
+            wall_anchor: (now, system_now),
+            rng: Rng::from_entropy(),
+            dropped: 0,
+            empty_behavior: EmptyBehavior::Exit,
+        };
+
+        for task in tasks {
+            scheduler.insert(task, epoch, system_epoch);
+        }
+
+        scheduler
+    }
+
+    /// Sets the handler invoked when a task panics.
+    ///
+    /// Without a handler the panic is only reported by the panic hook and the
+    /// task stays on its schedule.
+    pub fn with_panic_handler<F>(mut self, handler: F) -> Self
+    where
+        F: 'static + Send + FnMut(TaskId, &(dyn Any + Send)) -> PanicAction,
+    {
+        self.panic_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets the handler invoked with the errors of failed executions once they
+    /// are no longer retried.
+    ///
+    /// Without a handler such errors are discarded.
+    pub fn with_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: 'static + Send + FnMut(TaskId, &TaskError),
+    {
+        self.error_handler = Some(Box::new(handler));
+        self
+    }
+
+    /// Sets a callback invoked right before each execution of a task.
+    ///
+    /// Tasks executed by workers (see [`Scheduler::with_workers`]) do not
+    /// invoke the callbacks.
+    pub fn on_before<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Send + FnMut(&TaskId),
+    {
+        self.on_before = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a callback invoked right after each execution of a task with the
+    /// time it took according to the clock, including executions which
+    /// panicked.
+    ///
+    /// Tasks executed by workers (see [`Scheduler::with_workers`]) do not
+    /// invoke the callbacks.
+    pub fn on_after<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Send + FnMut(&TaskId, Duration),
+    {
+        self.on_after = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a callback invoked when an execution of a recurring task took
+    /// longer than its interval, with the time it took and the interval.
+    ///
+    /// Such a task can not keep up with its schedule. Overruns are also
+    /// counted in [`TaskStats::overruns`]. Tasks executed by workers (see
+    /// [`Scheduler::with_workers`]) are not timed and never overrun.
+    pub fn on_overrun<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Send + FnMut(&TaskId, Duration, Duration),
+    {
+        self.on_overrun = Some(Box::new(f));
+        self
+    }
+
+    /// Sets a callback invoked once an execution on a worker exceeded the
+    /// timeout of its task (see [`Task::timeout`]), with the time it ran so
+    /// far.
+    ///
+    /// Timeouts are noticed whenever the scheduler checks for due tasks.
+    pub fn on_timeout<F>(mut self, f: F) -> Self
+    where
+        F: 'static + Send + FnMut(&TaskId, Duration),
+    {
+        self.on_timeout = Some(Box::new(f));
+        self
+    }
+
+    /// Sets how recurring tasks handle missed executions, unless they set
+    /// their own [`Task::missed_ticks`].
+    ///
+    /// Without a policy each schedule keeps its default. Tasks with a
+    /// [`Schedule::FixedDelay`] are not affected, they always wait their
+    /// interval after an execution. One-shot tasks whose time already passed,
+    /// e.g. a [`Schedule::At`] in the past, execute once either way.
+    pub fn with_catch_up(mut self, policy: CatchUpPolicy) -> Self {
+        self.catch_up = Some(policy);
+
+        let mut tasks = self.schedule.drain();
+        for scheduled in &mut tasks {
+            Self::apply_catch_up(policy, &mut scheduled.task);
+        }
+        self.schedule.extend(tasks);
+
+        self
+    }
+
+    /// Sets the lower bound for intervals of recurring tasks.
+    ///
+    /// Every recurring task with a shorter interval (e.g.
+    /// `Schedule::Every(Duration::ZERO)`) is clamped to `min` instead of
+    /// being due all the time. Defaults to the [`Clock::min_interval`] of
+    /// the clock.
+    pub fn with_min_interval(mut self, min: Duration) -> Self {
+        self.min_interval = min;
+
+        let mut tasks = self.schedule.drain();
+        for scheduled in &mut tasks {
+            scheduled.task.schedule.clamp_interval(min);
+        }
+        self.schedule.extend(tasks);
+
+        self
+    }
+
+    /// Seeds the generator which random schedules like
+    /// [`Schedule::EveryJittered`] and [`Schedule::RandomInterval`] draw from,
+    /// so that their executions are reproducible, e.g. in tests.
+    ///
+    /// Every task draws from its own generator, forked off the scheduler's
+    /// one when the task is added, so its executions only depend on the seed
+    /// and the order in which tasks were added. Tasks which are already
+    /// scheduled, e.g. by [`Scheduler::with_tasks`], are reseeded in that
+    /// order, but keep the first execution they drew before. Without a seed
+    /// the generator is seeded from entropy.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::new(seed);
+
+        let mut tasks = self.schedule.drain();
+        tasks.sort_by_key(|scheduled| scheduled.seq);
+        for scheduled in &mut tasks {
+            scheduled.rng = self.rng.fork();
+        }
+        self.schedule.extend(tasks);
+
+        self
+    }
+
+    /// Sets the recorder receiving the events of the scheduler, in addition
+    /// to the statistics it keeps itself (see [`Scheduler::stats`]).
+    pub fn with_recorder(mut self, recorder: impl Recorder + 'static) -> Self {
+        self.recorder = Some(Box::new(recorder));
+        self
+    }
+
+    /// Sets the longest time the scheduler sleeps at once while running.
+    ///
+    /// Waiting for a task due later is split into sleeps of at most `slice`,
+    /// between which the scheduler checks again for due tasks and for
+    /// failures on workers. It never wakes up without pending tasks. The
+    /// slice is raised to at least [`DEFAULT_MIN_INTERVAL`]. Defaults to
+    /// [`DEFAULT_SLEEP_SLICE`].
+    pub fn with_sleep_slice(mut self, slice: Duration) -> Self {
+        self.sleep_slice = slice.max(DEFAULT_MIN_INTERVAL);
+        self
+    }
+
+    /// Sets whether the scheduler stops or waits for new tasks once no tasks
+    /// are left. Defaults to [`EmptyBehavior::Exit`].
+    ///
+    /// Blocking applies to the run loops which can be stopped:
+    /// [`SharedScheduler::run`] waits until a task is added, and
+    /// [`SharedScheduler::run_with_shutdown`] as well as
+    /// [`Scheduler::run_with_shutdown`] also until they are shut down. The
+    /// other run loops own the scheduler, so no tasks could be added to them,
+    /// and a [`Scheduler::spawn`]ed scheduler always waits for new tasks
+    /// while its handle is alive.
+    pub fn with_empty_behavior(mut self, behavior: EmptyBehavior) -> Self {
+        self.empty_behavior = behavior;
+        self
+    }
+
+    /// Busy-waits instead of sleeping once the next task is due within
+    /// `threshold`, e.g. two milliseconds, as sleeps may overshoot by about
+    /// that much on some platforms.
+    ///
+    /// Longer waits sleep until `threshold` before the next task and spin
+    /// the rest, so tasks execute more accurately at the cost of keeping a
+    /// core busy meanwhile. Applies to the run loops of the scheduler, e.g.
+    /// [`Scheduler::run`], but not to a spawned or shared scheduler. Spinning
+    /// is off by default.
+    pub fn with_spin_threshold(mut self, threshold: Duration) -> Self {
+        self.spin_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets how late an execution may start before it counts as overdue.
+    ///
+    /// Overdue executions are counted in [`TaskStats::overdue`] and logged
+    /// together with the [`MissedTickBehavior`] deciding how the task
+    /// continues; executions within the grace period are on time. Defaults
+    /// to [`DEFAULT_GRACE_PERIOD`].
+    pub fn with_grace_period(mut self, grace: Duration) -> Self {
+        self.grace_period = grace;
+        self
+    }
+
+    /// Executes at most `max_per` tasks within any window of `per`, across
+    /// all tasks.
+    ///
+    /// A due task exceeding the limit is deferred until the limit allows its
+    /// execution instead of executing in a burst. Deferred tasks execute in
+    /// the order they were deferred, among tasks of the same priority, so
+    /// none is deferred forever. Recurring tasks continue their schedule
+    /// from the time they were due rather than from the deferred execution.
+    /// `max_per` is raised to at least one.
+    pub fn with_rate_limit(mut self, max_per: usize, per: Duration) -> Self {
+        self.rate_limit = Some(RateLimit::new(max_per, per));
+        self
+    }
+
+    /// Keeps the tasks in a hierarchical timer wheel instead of a binary
+    /// heap.
+    ///
+    /// Adding and expiring a task takes amortized constant instead of
+    /// logarithmic time, which pays off once many thousands of tasks are
+    /// scheduled (see the `queue` benchmark). For few tasks the heap is
+    /// faster and uses less memory. Tasks still execute at their exact time
+    /// and in the same order; `resolution` only determines how tasks are
+    /// grouped internally and should be around their typical interval.
+    pub fn with_timer_wheel(mut self, resolution: Duration) -> Self {
+        let queue = std::mem::take(&mut self.schedule);
+        self.schedule = queue.into_wheel(self.clock.now(), resolution);
+        self
+    }
+
+    /// Schedules an additional task relative to now.
+    pub fn add(&mut self, task: Task<'a, T>) -> TaskId {
+        self.insert(task, self.clock.now(), self.clock.system_now())
+    }
+
+    /// Schedules an additional task once the task `dependency` executed,
+    /// relative to `delay` after that execution completed.
+    ///
+    /// Only the first execution of a recurring dependency arms the task,
+    /// executions skipped by [`Task::when`] do not count. If
+    /// the dependency is dropped before it executes, e.g. because it was
+    /// cancelled, the task is dropped as well. Tasks executed by workers arm
+    /// their dependents once they are dispatched. A dependency which is not
+    /// scheduled is treated as having executed right away.
+    ///
+    /// Waiting tasks are not counted by [`Scheduler::len`].
+    pub fn add_after(&mut self, dependency: TaskId, delay: Duration, task: Task<'a, T>) -> TaskId {
+        let id = self.next_task_id();
+
+        if self.is_pending(dependency) {
+            self.dependents
+                .entry(dependency)
+                .or_default()
+                .push((id, delay, task));
+        } else {
+            self.arm(id, delay, task, self.clock.now());
+        }
+
+        id
+    }
+
+    /// Moves the tasks of `other` into this scheduler, e.g. to run the
+    /// schedulers of several subsystems on one thread.
+    ///
+    /// The tasks keep their next execution, which requires both schedulers
+    /// to read the same clock, as well as their statistics, groups and
+    /// dependencies. Paused tasks stay paused. The tasks get new ids, the
+    /// returned map translates the ids of `other` into them. The handlers
+    /// and settings of `other` are dropped.
+    pub fn merge(&mut self, other: Scheduler<'a, C, T>) -> HashMap<TaskId, TaskId> {
+        let mut ids = HashMap::new();
+        let mut rekey =
+            |scheduler: &mut Self, id| *ids.entry(id).or_insert_with(|| scheduler.next_task_id());
+
+        let Scheduler {
+            mut schedule,
+            cancelled,
+            paused,
+            dependents,
+            stats,
+            ..
+        } = other;
+        let mut grouped = Vec::new();
+
+        for mut task in schedule.drain() {
+            if cancelled.contains(&task.id) {
+                continue;
+            }
+
+            task.id = rekey(self, task.id);
+            task.seq = self.next_seq;
+            self.next_seq += 1;
+
+            if let Some(group) = &task.task.group {
+                grouped.push((Arc::clone(group), task.id));
+            }
+
+            self.wake_if_next(task.at);
+            self.schedule.push(task);
+        }
+
+        for (_, mut task) in paused {
+            task.id = rekey(self, task.id);
+
+            if let Some(group) = &task.task.group {
+                grouped.push((Arc::clone(group), task.id));
+            }
+
+            self.paused.insert(task.id, task);
+        }
+
+        // Tasks waiting for a cancelled task, directly or through other
+        // waiting tasks, can never be armed and are dropped.
+        let mut dropped = cancelled.clone();
+        let mut grew = true;
+        while grew {
+            grew = false;
+            for (dependency, waiting) in &dependents {
+                if dropped.contains(dependency) {
+                    for (id, ..) in waiting {
+                        grew |= dropped.insert(*id);
+                    }
+                }
+            }
+        }
+
+        for (dependency, waiting) in dependents {
+            if dropped.contains(&dependency) {
+                for (id, ..) in waiting {
+                    self.record_drop(id);
+                }
+                continue;
+            }
+
+            let dependency = rekey(self, dependency);
+
+            for (id, delay, task) in waiting {
+                let id = rekey(self, id);
+                self.dependents
+                    .entry(dependency)
+                    .or_default()
+                    .push((id, delay, task));
+            }
+        }
+
+        // The statistics of cancelled tasks are dropped with them.
+        for (id, task_stats) in stats {
+            if let Some(id) = ids.get(&id) {
+                self.stats.insert(*id, task_stats);
+            }
+        }
+
+        for (group, id) in grouped {
+            self.groups.entry(group).or_default().insert(id);
+        }
+
+        ids
+    }
+
+    /// Schedules an additional task relative to now, discarding its id.
+    pub fn push(&mut self, task: Task<'a, T>) {
+        self.add(task);
+    }
+
+    /// The number of scheduled tasks.
+    pub fn len(&self) -> usize {
+        self.schedule.len() - self.cancelled.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of tasks the scheduler holds without reallocating.
+    ///
+    /// Cancelled tasks keep occupying space until they would have been due.
+    /// With [`Scheduler::with_timer_wheel`] this is just the number of
+    /// scheduled tasks, as the wheel spreads them over many slots.
+    pub fn capacity(&self) -> usize {
+        self.schedule.capacity()
+    }
+
+    /// Reserves space for at least `additional` more tasks, avoiding
+    /// reallocations while many tasks are added and cancelled.
+    ///
+    /// Has no effect with [`Scheduler::with_timer_wheel`].
+    pub fn reserve(&mut self, additional: usize) {
+        self.schedule.reserve(additional);
+        self.cancelled.reserve(additional);
+    }
+
+    /// Shrinks the space held for tasks as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.schedule.shrink_to_fit();
+        self.cancelled.shrink_to_fit();
+    }
+
+    /// Cancels the task with the given id, including all future executions
+    /// of recurring tasks.
+    ///
+    /// Returns `false` if the task is not (or no longer) scheduled.
+    pub fn cancel(&mut self, id: TaskId) -> bool {
+        if let Some(task) = self.paused.remove(&id) {
+            self.record_drop(id);
+            self.forget(id, task.task.group());
+            return true;
+        }
+
+        for dependents in self.dependents.values_mut() {
+            if let Some(index) = dependents
+                .iter()
+                .position(|(dependent, ..)| *dependent == id)
+            {
+                let (_, _, task) = dependents.swap_remove(index);
+                self.record_drop(id);
+                self.forget(id, task.group());
+                return true;
+            }
+        }
+
+        self.schedule.contains(id) && self.cancelled.insert(id)
+    }
+
+    /// Cancels all tasks of a group, see [`Scheduler::cancel`].
+    ///
+    /// Returns the number of tasks which were cancelled.
+    pub fn cancel_group(&mut self, group: &str) -> usize {
+        let Some(ids) = self.groups.get(group) else {
+            return 0;
+        };
+
+        let ids: Vec<_> = ids.iter().copied().collect();
+        ids.into_iter().filter(|id| self.cancel(*id)).count()
+    }
+
+    /// Takes a task off the schedule until it is resumed, keeping its id and
+    /// statistics.
+    ///
+    /// Paused tasks are not counted by [`Scheduler::len`], so they do not
+    /// keep [`Scheduler::run`] from returning. Returns `false` if the task is
+    /// not scheduled or already paused.
+    pub fn pause(&mut self, id: TaskId) -> bool {
+        if self.cancelled.contains(&id) {
+            return false;
+        }
+
+        let Some(task) = self.schedule.remove(id) else {
+            return false;
+        };
+
+        debug!("Paused task {}", task.label());
+        self.paused.insert(id, task);
+
+        true
+    }
+
+    /// Puts a paused task back on its schedule, computing its next execution
+    /// from now as if the task was just added.
+    ///
+    /// Returns `false` if the task is not paused.
+    pub fn resume(&mut self, id: TaskId) -> bool {
+        let Some(task) = self.paused.remove(&id) else {
+            return false;
+        };
+
+        self.insert_with_id(
+            id,
+            task.into_task(),
+            self.clock.now(),
+            self.clock.system_now(),
+        );
+
+        true
+    }
+
+    pub fn is_paused(&self, id: TaskId) -> bool {
+        self.paused.contains_key(&id)
+    }
+
+    /// Executes a scheduled or paused task once right away, e.g. to refresh
+    /// on demand, without changing when it executes next.
+    ///
+    /// The execution does not count towards the schedule, so a
+    /// [`Schedule::Counted`] task keeps its remaining count and a
+    /// [`Schedule::SelfDirected`] one the delay it was directed to, and it is
+    /// not recorded in the statistics of the task. With workers the execution
+    /// is dispatched like a regular one, including the delay it directs the
+    /// task to. Panics are passed to the panic handler
+    /// and errors to the error handler without being retried. Returns `false`
+    /// if the task is not scheduled or paused.
+    pub fn fire_now(&mut self, id: TaskId) -> bool {
+        if self.cancelled.contains(&id) {
+            return false;
+        }
+
+        let (mut task, paused) = match self.paused.remove(&id) {
+            Some(task) => (task, true),
+            None => match self.schedule.remove(id) {
+                Some(task) => (task, false),
+                None => return false,
+            },
+        };
+
+        debug!("Executing task {} out of band", task.label());
+
+        if let Some(pool) = &self.pool {
+            let now = self.clock.now();
+            pool.dispatch(id, &mut task.task.f, task.task.timeout, now);
+        } else {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| task.task.f.call()));
+            // The delay returned out of band does not direct the task.
+            task.task.f.take_directed();
+
+            match result {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => self.handle_error(task.label(), &error),
+                Err(payload) => {
+                    if !self.keep_after_panic(id, payload) {
+                        self.record_drop(id);
+                        self.forget(id, task.task.group());
+                        return true;
+                    }
+                }
+            }
+        }
+
+        if task.task.f.is_spent() {
+            debug!("Task {} completed its schedule", task.label());
+            self.forget(id, task.task.group());
+        } else if paused {
+            self.paused.insert(id, task);
+        } else {
+            self.schedule.push(task);
+        }
+
+        true
+    }
+
+    /// Freezes the timing of all tasks until [`Scheduler::resume_all`].
+    ///
+    /// While frozen no tasks are executed and [`Scheduler::run_pending`]
+    /// reports one sleep slice until the next check. [`Scheduler::run`] and
+    /// [`Scheduler::run_n`] return right away, as nothing could resume the
+    /// scheduler while they run.
+    pub fn pause_all(&mut self) {
+        self.paused_since.get_or_insert(self.clock.now());
+    }
+
+    /// Resumes the timing frozen by [`Scheduler::pause_all`].
+    ///
+    /// Every task is moved back by the time the scheduler was frozen, so the
+    /// tasks keep their distances instead of all becoming due at once.
+    /// Wall-clock tasks such as [`Schedule::At`] keep their time.
+    pub fn resume_all(&mut self) {
+        let Some(since) = self.paused_since.take() else {
+            return;
+        };
+
+        let offset = self.clock.now().since(since);
+        debug!("Resuming all tasks after {offset:?}");
+
+        let mut tasks = self.schedule.drain();
+        for task in tasks.iter_mut().filter(|task| task.wall.is_none()) {
+            task.shift(offset);
+        }
+        self.schedule.extend(tasks);
+    }
+
+    /// Removes all tasks.
+    pub fn clear(&mut self) {
+        self.schedule.drain();
+        self.paused.clear();
+        self.cancelled.clear();
+        self.stats.clear();
+        self.groups.clear();
+        self.dependents.clear();
+    }
+
+    /// Keeps only the tasks for which `f` returns `true`, removing the others
+    /// right away. Paused tasks are included.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&ScheduledTask<'a, T>) -> bool,
+    {
+        let mut tasks = self.schedule.drain();
+        let mut paused = std::mem::take(&mut self.paused);
+
+        paused.retain(|id, task| {
+            let keep = f(task);
+
+            if !keep {
+                self.record_drop(*id);
+                self.forget(*id, task.task.group());
+            }
+
+            keep
+        });
+        self.paused = paused;
+
+        tasks.retain(|task| {
+            let keep = !self.cancelled.remove(&task.id) && f(task);
+
+            if !keep {
+                self.record_drop(task.id);
+                self.forget(task.id, task.task.group());
+            }
+
+            keep
+        });
+
+        self.schedule.extend(tasks);
+    }
+
+    /// Replaces the schedule of a task, computing its next execution from
+    /// now as if the task was just added.
+    ///
+    /// Returns `false` if the task is not (or no longer) scheduled. If the new
+    /// schedule never executes the task, e.g. a [`Schedule::Counted`] with a
+    /// count of zero, the task is removed.
+    pub fn reschedule_task(&mut self, id: TaskId, schedule: Schedule) -> bool {
+        if self.cancelled.contains(&id) {
+            return false;
+        }
+
+        let Some(scheduled) = self.schedule.remove(id) else {
+            return false;
+        };
+
+        let mut task = scheduled.into_task();
+        let group = task.group.clone();
+        task.schedule = schedule;
+        self.insert_with_id(id, task, self.clock.now(), self.clock.system_now());
+
+        if self.get(id).is_none() {
+            self.forget(id, group.as_deref());
+        }
+
+        true
+    }
+
+    /// When a scheduled task executes next.
+    pub fn next_fire(&self, id: TaskId) -> Option<Stbi> {
+        self.get(id).map(|task| task.at)
+    }
+
+    /// Overrides when a scheduled task executes next, e.g. to snooze it,
+    /// regardless of its schedule.
+    ///
+    /// Recurring tasks continue their schedule from the overridden
+    /// execution, e.g. a [`Schedule::Every`] task executes one interval
+    /// after it. Returns `false` if the task is not (or no longer) scheduled.
+    pub fn set_next_fire(&mut self, id: TaskId, at: Stbi) -> bool {
+        if self.cancelled.contains(&id) {
+            return false;
+        }
+
+        let Some(mut task) = self.schedule.remove(id) else {
+            return false;
+        };
+
+        let (now, system_now) = (self.clock.now(), self.clock.system_now());
+        task.at = at;
+        task.deferred = None;
+        task.wall = task.wall.map(|_| match at.checked_since(now) {
+            Some(ahead) => system_now + ahead,
+            None => system_now - now.since(at),
+        });
+
+        trace!("Moved task {} to {at}", task.label());
+
+        self.wake_if_next(at);
+        self.schedule.push(task);
+
+        true
+    }
+
+    /// The number of executions left for a scheduled [`Schedule::Counted`]
+    /// task.
+    pub fn remaining(&self, id: TaskId) -> Option<usize> {
+        self.get(id)?.task.schedule.remaining()
+    }
+
+    /// Timing statistics of the tasks which are still scheduled and executed
+    /// at least once.
+    pub fn stats(&self) -> HashMap<TaskId, TaskStats> {
+        self.stats.clone()
+    }
+
+    /// Removes all tasks in the order they are due, e.g. to add them to
+    /// another scheduler.
+    ///
+    /// Recurring tasks only carry their remaining executions. Paused tasks
+    /// come last.
+    pub fn into_tasks(self) -> Vec<Task<'a, T>> {
+        self.into_iter().map(ScheduledTask::into_task).collect()
+    }
+
+    /// Removes all tasks together with their timing, in the order they are
+    /// due followed by the paused tasks.
+    pub fn into_scheduled(self) -> Vec<ScheduledTask<'a, T>> {
+        self.into_iter().collect()
+    }
+
+    /// Registers a waker which is woken whenever an added task becomes the
+    /// next one due, replacing a previously registered waker.
+    ///
+    /// This allows driving the scheduler from an external event loop, which
+    /// parks until [`Scheduler::time_until_next`] passed or the waker was
+    /// woken and then calls [`Scheduler::run_pending`]. The waker stays
+    /// registered until it is replaced or [`Scheduler::clear_waker`] is
+    /// called.
+    pub fn register_waker(&mut self, waker: Waker) {
+        self.waker = Some(waker);
+    }
+
+    /// Removes the waker registered by [`Scheduler::register_waker`].
+    pub fn clear_waker(&mut self) {
+        self.waker = None;
+    }
+
+    /// Returns a handle reading whether the scheduler currently sleeps,
+    /// executes tasks or is not running, e.g. for a watchdog thread.
+    ///
+    /// The state is [`SchedulerState::Stopped`] until the scheduler runs for
+    /// the first time and after a run loop like [`Scheduler::run`] returned.
+    pub fn state_handle(&self) -> StateHandle {
+        self.state.clone()
+    }
+
+    /// The time until the next task is due, saturating to zero for overdue
+    /// tasks.
+    pub fn time_until_next(&self) -> Option<Duration> {
+        Some(self.peek()?.at.since(self.clock.now()))
+    }
+
+    /// Describes the task which is due next without executing it.
+    pub fn peek_next(&self) -> Option<NextInfo> {
+        self.peek().map(ScheduledTask::info)
+    }
+
+    /// Describes the next executions of up to `k` tasks in the order they
+    /// are due, without executing them.
+    ///
+    /// Each task is listed with its next execution only, recurring tasks do
+    /// not appear repeatedly. Paused tasks are not included.
+    pub fn next_wakeups(&self, k: usize) -> Vec<NextInfo> {
+        let mut tasks: Vec<_> = self
+            .schedule
+            .iter()
+            .filter(|task| !self.cancelled.contains(&task.id))
+            .collect();
+
+        // Tasks which are due earlier compare greater.
+        tasks.sort_unstable_by(|a, b| b.cmp(a));
+        tasks.into_iter().take(k).map(ScheduledTask::info).collect()
+    }
+
+    /// The next task which is not cancelled.
+    fn peek(&self) -> Option<&ScheduledTask<'a, T>> {
+        match self.schedule.peek() {
+            Some(top) if !self.cancelled.contains(&top.id) => Some(top),
+            _ => self
+                .schedule
+                .iter()
+                .filter(|task| !self.cancelled.contains(&task.id))
+                .max(),
+        }
+    }
+
+    fn get(&self, id: TaskId) -> Option<&ScheduledTask<'a, T>> {
+        if self.cancelled.contains(&id) {
+            return None;
+        }
+
+        self.schedule.get(id)
+    }
+
+    /// Wakes the registered waker if a task due at `at` becomes the next
+    /// one, see [`Scheduler::register_waker`].
+    fn wake_if_next(&self, at: Stbi) {
+        if let Some(waker) = &self.waker {
+            // Earlier times compare greater.
+            if self.peek().is_none_or(|next| at > next.at) {
+                waker.wake_by_ref();
+            }
+        }
+    }
+
+    fn next_task_id(&mut self) -> TaskId {
+        let id = TaskId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    fn apply_catch_up(policy: CatchUpPolicy, task: &mut Task<'a, T>) {
+        if task.missed_ticks.is_none() && !matches!(task.schedule, Schedule::FixedDelay(_)) {
+            task.missed_ticks = Some(policy.missed_ticks());
+        }
+    }
+
+    /// Whether the task is yet to execute, i.e. it is scheduled, paused or
+    /// waiting for another task.
+    fn is_pending(&self, id: TaskId) -> bool {
+        self.paused.contains_key(&id)
+            || (!self.cancelled.contains(&id) && self.schedule.contains(id))
+            || self
+                .dependents
+                .values()
+                .flatten()
+                .any(|(dependent, ..)| *dependent == id)
+    }
+
+    /// Schedules a task waiting for another one relative to `delay` after
+    /// `now`.
+    fn arm(&mut self, id: TaskId, delay: Duration, task: Task<'a, T>, now: Stbi) {
+        let system_now = self.clock.system_now();
+        let system_then = system_now.checked_add(delay).unwrap_or(system_now);
+        self.insert_with_id(id, task, now + delay, system_then);
+    }
+
+    fn insert(&mut self, task: Task<'a, T>, now: Stbi, system_now: SystemTime) -> TaskId {
+        let id = self.next_task_id();
+        self.insert_with_id(id, task, now, system_now);
+        id
+    }
+
+    fn insert_with_id(&mut self, id: TaskId, task: Task<'a, T>, now: Stbi, system_now: SystemTime) {
+        self.insert_with(id, task, now, system_now, |schedule, rng| {
+            schedule.first_at(now, system_now, rng)
+        });
+    }
+
+    /// Enqueues the task, `first` computing the time of its first execution.
+    fn insert_with<F>(
+        &mut self,
+        id: TaskId,
+        mut task: Task<'a, T>,
+        now: Stbi,
+        system_now: SystemTime,
+        first: F,
+    ) where
+        F: FnOnce(&Schedule, &mut Rng) -> Stbi,
+    {
+        task.schedule.clamp_interval(self.min_interval);
+
+        if let Some(policy) = self.catch_up {
+            Self::apply_catch_up(policy, &mut task);
+        }
+
+        if task.schedule.is_exhausted(system_now) {
+            return;
+        }
+
+        let mut rng = self.rng.fork();
+        let at = if task.immediate {
+            now
+        } else {
+            first(&task.schedule, &mut rng)
+        };
+        let wall = task
+            .schedule
+            .is_wall_clock()
+            .then(|| system_now + at.since(now));
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        debug!(
+            "Scheduled task {} in {:?}",
+            Label(id, task.name()),
+            at.since(now)
+        );
+
+        if let Some(group) = &task.group {
+            self.groups.entry(Arc::clone(group)).or_default().insert(id);
+        }
+
+        self.wake_if_next(at);
+
+        self.schedule.push(ScheduledTask {
+            id,
+            at,
+            wall,
+            seq,
+            task,
+            rng,
+            retry: None,
+            deferred: None,
+        });
+    }
+
+    pub fn run(mut self) -> RunSummary {
+        let _stopped = self.state.stop_on_drop();
+        let start = self.start_summary();
+        let mut fired = 0;
+
+        while self.paused_since.is_none() {
+            let outcome = self.run_pending_while(|_| true);
+            fired += outcome.ran;
+
+            let Some(diff) = outcome.next else {
+                break;
+            };
+
+            self.sleep(diff);
+        }
+
+        self.summary(start, fired)
+    }
+
+    /// Runs the scheduler until `token` is shut down or no tasks are left,
+    /// unless it blocks while empty (see [`Scheduler::with_empty_behavior`]).
+    ///
+    /// A task which is currently executing is finished before returning.
+    pub fn run_with_shutdown(mut self, token: ShutdownToken) {
+        let _stopped = self.state.stop_on_drop();
+
+        while !token.is_shutdown() {
+            let diff = match self.run_pending_while(|_| !token.is_shutdown()).next {
+                Some(diff) => diff,
+                None if self.empty_behavior == EmptyBehavior::Block => SHUTDOWN_POLL_INTERVAL,
+                None => return,
+            };
+
+            if !token.is_shutdown() {
+                self.sleep(diff.min(SHUTDOWN_POLL_INTERVAL));
+            }
+        }
+    }
+
+    /// Runs the scheduler until `deadline` passed or no tasks are left.
+    ///
+    /// Never sleeps past `deadline`, even if the next task is due later.
+    pub fn run_until(&mut self, deadline: Stbi) -> RunSummary {
+        let _stopped = self.state.stop_on_drop();
+        let start = self.start_summary();
+        let mut fired = 0;
+
+        loop {
+            let outcome = self.run_pending_while(|_| true);
+            fired += outcome.ran;
+
+            let Some(diff) = outcome.next else {
+                break;
+            };
+            let left = deadline.since(self.clock.now());
+
+            if left.is_zero() {
+                break;
+            }
+
+            self.sleep(diff.min(left));
+        }
+
+        self.summary(start, fired)
+    }
+
+    /// Runs the scheduler until `n` tasks were executed or no tasks are left.
+    ///
+    /// Sleeps between executions like [`Scheduler::run`]. Dispatching a task
+    /// to a worker counts as an execution.
+    pub fn run_n(&mut self, n: usize) -> RunSummary {
+        let _stopped = self.state.stop_on_drop();
+        let start = self.start_summary();
+
+        let mut ran = 0;
+
+        while ran < n && self.paused_since.is_none() {
+            let outcome = self.run_pending_while(|executed| ran + executed < n);
+            ran += outcome.ran;
+
+            let Some(diff) = outcome.next else {
+                break;
+            };
+
+            if ran < n {
+                self.sleep(diff);
+            }
+        }
+
+        self.summary(start, ran)
+    }
+
+    /// The time and the number of dropped tasks at the start of a run.
+    fn start_summary(&self) -> (Stbi, usize) {
+        (self.clock.now(), self.dropped)
+    }
+
+    /// Summarizes a run which started with `start` and executed `fired`
+    /// tasks.
+    fn summary(&self, (started, dropped): (Stbi, usize), fired: usize) -> RunSummary {
+        RunSummary {
+            fired,
+            dropped: self.dropped - dropped,
+            elapsed: self.clock.now().since(started),
+        }
+    }
+
+    /// Sleeps for `duration`, but at most one sleep slice.
+    ///
+    /// Returns right away for a zero `duration`, e.g. when tasks became due
+    /// while others executed, so that due tasks run back-to-back.
+    fn sleep(&self, duration: Duration) {
+        if duration.is_zero() {
+            return;
+        }
+
+        let duration = duration.min(self.sleep_slice);
+
+        match self.spin_threshold {
+            Some(threshold) if duration <= threshold => {
+                trace!("Spinning for {duration:?}");
+                self.clock.spin(duration);
+            }
+            Some(threshold) => {
+                // Wakes up early, the remaining wait is spun.
+                trace!("Sleeping for {:?}", duration - threshold);
+                self.clock.sleep(duration - threshold);
+            }
+            None => {
+                trace!("Sleeping for {duration:?}");
+                self.clock.sleep(duration);
+            }
+        }
+    }
+
+    /// Executes every task which is due without blocking.
+    ///
+    /// Returns the time until the next task is due, but at most
+    /// [`WALL_CLOCK_SLICE`], or `None` if no tasks are left.
+    pub fn run_pending(&mut self) -> Option<Duration> {
+        self.run_pending_while(|_| true).next
+    }
+
+    /// Executes every task due at or before `now`, treating `now` as the
+    /// current time instead of reading the clock.
+    ///
+    /// Tasks are rescheduled as if they completed at `now`, which allows
+    /// stepping through virtual time deterministically. While frozen by
+    /// [`Scheduler::pause_all`] no tasks are executed.
+    pub fn tick(&mut self, now: Stbi) -> TickOutcome {
+        self.handle_pool_failures();
+
+        if self.paused_since.is_some() {
+            return TickOutcome {
+                ran: 0,
+                next: self.until_next(now).map(|_| self.sleep_slice),
+            };
+        }
+
+        let mut ran = 0;
+
+        while !self.is_saturated() && self.next_lateness(now).is_some() {
+            if self.defer_over_limit(now) {
+                continue;
+            }
+
+            let (fired, next) = self.execute_next(now);
+
+            if let Some((task, result)) = next {
+                self.push_next(task, result, fired, now, now);
+            }
+
+            if fired == Fired::Ran {
+                ran += 1;
+            }
+        }
+
+        TickOutcome {
+            ran,
+            next: self.until_next(now),
+        }
+    }
+
+    /// Removes every task due at or before `now` and returns it instead of
+    /// executing it, leaving the execution to the caller.
+    ///
+    /// Recurring tasks stay scheduled as if they completed at `now`. Their
+    /// returned tasks share the function with the scheduled ones, so a
+    /// returned task blocks later executions of the same function until its
+    /// own execution completed. The returned tasks execute once when they are
+    /// added to a scheduler, or by [`Task::call`]. The callbacks of this
+    /// scheduler are not invoked and errors are neither retried nor reported
+    /// to its error handler.
+    pub fn drain_due(&mut self, now: Stbi) -> Vec<Task<'a, T>> {
+        let mut due = Vec::new();
+
+        while self.next_lateness(now).is_some() {
+            let Some(mut task) = self.schedule.pop() else {
+                break;
+            };
+
+            self.record_stats(&task, now.since(task.at), None);
+            task.direct();
+
+            if let Some(shared) = task.task.f.share() {
+                let job = Job::Shared(shared);
+                due.push(Task::new(Schedule::Once(None), job).priority(task.task.priority));
+            }
+
+            self.push_next(task, Ok(()), Fired::Ran, now, now);
+        }
+
+        due
+    }
+
+    /// Like [`Scheduler::run_pending`], but checks `proceed` with the number
+    /// of tasks executed so far before every task.
+    fn run_pending_while<F>(&mut self, mut proceed: F) -> TickOutcome
+    where
+        F: FnMut(usize) -> bool,
+    {
+        self.handle_pool_failures();
+
+        if self.paused_since.is_some() {
+            self.state.set(SchedulerState::Idle);
+
+            return TickOutcome {
+                ran: 0,
+                next: self.until_next(self.clock.now()).map(|_| self.sleep_slice),
+            };
+        }
+
+        let now = self.clock.now();
+        self.sync_wall_clock(now);
+
+        let mut ran = 0;
+        self.state.set(SchedulerState::Firing);
+
+        while proceed(ran) && !self.is_saturated() && self.next_lateness(now).is_some() {
+            if self.fire_next() == Fired::Ran {
+                ran += 1;
+            }
+        }
+
+        self.state.set(SchedulerState::Idle);
+
+        let next = self.until_next(self.clock.now());
+
+        let next = if self.is_saturated() {
+            next.map(|diff| diff.max(IN_FLIGHT_POLL_INTERVAL))
+        } else {
+            next
+        };
+
+        TickOutcome { ran, next }
+    }
+
+    /// Whether the workers execute as many tasks as may be dispatched at
+    /// once.
+    fn is_saturated(&self) -> bool {
+        match (&self.pool, self.max_in_flight) {
+            (Some(pool), Some(max)) => pool.in_flight() >= max,
+            _ => false,
+        }
+    }
+
+    /// Returns `false` if the task should be dropped after it panicked.
+    fn keep_after_panic(&mut self, id: TaskId, payload: Box<dyn Any + Send>) -> bool {
+        let action = match &mut self.panic_handler {
+            Some(handler) => handler(id, payload.as_ref()),
+            None => PanicAction::Reschedule,
+        };
+
+        match action {
+            PanicAction::Drop => {
+                debug!("Dropping task {id:?} after it panicked");
+                false
+            }
+            PanicAction::Reschedule => true,
+            PanicAction::Propagate => panic::resume_unwind(payload),
+        }
+    }
+
+    /// Handles failures of tasks executed by the pool.
+    ///
+    /// The tasks were already rescheduled when they were dispatched, so
+    /// errors can not be retried.
+    fn handle_pool_failures(&mut self) {
+        let Some(pool) = &self.pool else {
+            return;
+        };
+
+        let failures: Vec<_> = pool.failures().collect();
+        let timed_out = pool.timed_out(self.clock.now());
+
+        for (id, elapsed) in timed_out {
+            self.record_timeout(id, elapsed);
+        }
+
+        for (id, failure) in failures {
+            match failure {
+                Failure::Panic(payload) => {
+                    if !self.keep_after_panic(id, payload) {
+                        self.cancel(id);
+                    }
+                }
+                Failure::Error(error) => self.handle_error(Label(id, None), &error),
+            }
+        }
+    }
+
+    /// Returns the time from `now` until the next task, dropping cancelled
+    /// tasks on the way.
+    fn until_next(&mut self, now: Stbi) -> Option<Duration> {
+        while let Some(top) = self.schedule.peek() {
+            if self.cancelled.remove(&top.id) {
+                debug!("Dropping cancelled task {}", top.label());
+
+                if let Some(task) = self.schedule.pop() {
+                    self.record_drop(task.id);
+                    self.forget(task.id, task.task.group());
+                }
+
+                continue;
+            }
+
+            return Some(top.at.since(now).min(WALL_CLOCK_SLICE));
+        }
+
+        None
+    }
+
+    /// How long the next task is overdue at `now`, zero if it is exactly due,
+    /// or `None` if it is not due yet or no tasks are left.
+    ///
+    /// Unlike the time until the next task this distinguishes a task which
+    /// is due right now from one which is due later, both of which
+    /// [`Stbi::since`] saturates to zero.
+    fn next_lateness(&mut self, now: Stbi) -> Option<Duration> {
+        self.until_next(now)?;
+        self.schedule
+            .peek()
+            .and_then(|next| now.checked_since(next.at))
+    }
+
+    /// Maps the wall-clock tasks onto the monotonic clock again if the system
+    /// clock jumped since they were last mapped, otherwise only the next task
+    /// (see [`Scheduler::follow_wall_clock`]).
+    ///
+    /// [`Schedule::At`] tasks keep their time, so they are executed right away
+    /// if the clock jumped past it. A pending occurrence of a calendar
+    /// schedule such as [`Schedule::Cron`] is moved forward to the first one
+    /// after the new time if the clock was set back, and executed right away
+    /// if it was skipped.
+    fn sync_wall_clock(&mut self, now: Stbi) {
+        let system_now = self.clock.system_now();
+        let (anchor, system_anchor) = self.wall_anchor;
+        let expected = system_anchor + now.since(anchor);
+
+        let deviation = match system_now.duration_since(expected) {
+            Ok(ahead) => ahead,
+            Err(behind) => behind.duration(),
+        };
+
+        if deviation <= WALL_CLOCK_TOLERANCE {
+            return self.follow_wall_clock(now, system_now);
+        }
+
+        self.wall_anchor = (now, system_now);
+
+        let mut tasks = self.schedule.drain();
+        for task in &mut tasks {
+            let Some(mut wall) = task.wall else {
+                continue;
+            };
+
+            if task.task.schedule.is_calendar() && wall > system_now {
+                wall = task
+                    .task
+                    .schedule
+                    .next_occurrence(system_now)
+                    .unwrap_or(wall);
+            }
+
+            task.wall = Some(wall);
+
+            if task.retry.is_none() {
+                task.at = now + Schedule::until(wall, system_now);
+            }
+        }
+        self.schedule.extend(tasks);
+    }
+
+    /// Derives the `at` of the next task from its wall-clock time again,
+    /// using the current offset between the clocks, if it is a wall-clock
+    /// task.
+    ///
+    /// This follows gradual adjustments of the system clock, e.g. by NTP,
+    /// which stay within the tolerance of a jump. A task which is already due
+    /// keeps its `at` for its lateness, and retried or deferred executions
+    /// keep theirs.
+    fn follow_wall_clock(&mut self, now: Stbi, system_now: SystemTime) {
+        let Some(next) = self.schedule.peek() else {
+            return;
+        };
+
+        let Some(wall) = next.wall else {
+            return;
+        };
+
+        if next.retry.is_some() || next.deferred.is_some() {
+            return;
+        }
+
+        let at = now + Schedule::until(wall, system_now);
+        if at == next.at || (at == now && now.checked_since(next.at).is_some()) {
+            return;
+        }
+
+        if let Some(mut task) = self.schedule.remove(next.id) {
+            trace!("Moved task {} to {at} by the wall-clock", task.label());
+            task.at = at;
+            self.schedule.push(task);
+        }
+    }
+
+    /// Executes the next task unless it is skipped or held back.
+    fn fire_next(&mut self) -> Fired {
+        if self.defer_over_limit(self.clock.now()) {
+            return Fired::Held;
+        }
+
+        let fired_at = self.clock.now();
+        let (fired, next) = self.execute_next(fired_at);
+
+        if let Some((task, result)) = next {
+            self.push_next(task, result, fired, fired_at, self.clock.now());
+        }
+
+        fired
+    }
+
+    /// Moves the next task to the time the rate limit allows its execution,
+    /// returning `true` if the limit is exhausted at `now`.
+    fn defer_over_limit(&mut self, now: Stbi) -> bool {
+        let Some(limit) = &mut self.rate_limit else {
+            return false;
+        };
+
+        let Err(available) = limit.acquire(now) else {
+            return false;
+        };
+
+        if let Some(mut task) = self.schedule.pop() {
+            trace!(
+                "Deferring task {} by {:?} due to the rate limit",
+                task.label(),
+                available.since(now)
+            );
+
+            task.deferred.get_or_insert(task.at);
+            task.at = available;
+            // A fresh sequence number queues the task behind those deferred
+            // before it.
+            task.seq = self.next_seq;
+            self.next_seq += 1;
+            self.schedule.push(task);
+        }
+
+        true
+    }
+
+    /// Pops the next task and executes it, returning what became of it
+    /// together with the task and the result of the execution unless it
+    /// should be dropped or was held back by [`OverlapPolicy::Delay`].
+    ///
+    /// Tasks dispatched to the pool always succeed here, their errors are
+    /// reported once they completed.
+    fn execute_next(&mut self, now: Stbi) -> (Fired, Option<Executed<'a, T>>) {
+        let (mut task, lateness) = match self.begin_next(now) {
+            Next::Execute(task, lateness) => (task, lateness),
+            Next::Done(fired, next) => return (fired, next),
+        };
+
+        let started = self.clock.now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| task.task.f.call()));
+
+        self.finish_next(task, lateness, started, result)
+    }
+
+    /// Pops the next task and prepares its execution, returning what became
+    /// of it unless it is to be executed by the caller.
+    ///
+    /// Invokes the [`Scheduler::on_before`] callback, the caller executes the
+    /// task and passes the result to [`Scheduler::finish_next`].
+    fn begin_next(&mut self, now: Stbi) -> Next<'a, T> {
+        let Some(mut task) = self.schedule.pop() else {
+            return Next::Done(Fired::Held, None);
+        };
+        let lateness = now.since(task.deferred.unwrap_or(task.at));
+        if let Some(condition) = &mut task.task.condition {
+            if !condition() {
+                debug!(
+                    "Skipping task {}, its condition does not hold",
+                    task.label()
+                );
+                return Next::Done(Fired::Skipped, Some((task, Ok(()))));
+            }
+        }
+
+        debug!("Executing task {}, {lateness:?} late", task.label());
+
+        if let Some(pool) = &self.pool {
+            if pool.is_in_flight(task.id) {
+                match task.task.overlap {
+                    Some(OverlapPolicy::Skip) => {
+                        debug!("Skipping task {}, it is still running", task.label());
+                        return Next::Done(Fired::Skipped, Some((task, Ok(()))));
+                    }
+                    Some(OverlapPolicy::Delay) => {
+                        task.deferred.get_or_insert(task.at);
+                        task.at = now + IN_FLIGHT_POLL_INTERVAL;
+                        self.schedule.push(task);
+                        return Next::Done(Fired::Held, None);
+                    }
+                    None => {}
+                }
+            }
+
+            task.direct();
+            pool.dispatch(task.id, &mut task.task.f, task.task.timeout, now);
+            self.record_stats(&task, lateness, None);
+            return Next::Done(Fired::Ran, Some((task, Ok(()))));
+        }
+
+        if let Some(on_before) = &mut self.on_before {
+            on_before(&task.id);
+        }
+
+        Next::Execute(task, lateness)
+    }
+
+    /// Completes an execution prepared by [`Scheduler::begin_next`] which
+    /// started at `started`, recording it and handling a panic.
+    fn finish_next(
+        &mut self,
+        mut task: ScheduledTask<'a, T>,
+        lateness: Duration,
+        started: Stbi,
+        result: thread::Result<Result<(), TaskError>>,
+    ) -> (Fired, Option<Executed<'a, T>>) {
+        let elapsed = self.clock.now().since(started);
+        task.direct();
+
+        if let Some(on_after) = &mut self.on_after {
+            on_after(&task.id, elapsed);
+        }
+
+        self.record_stats(&task, lateness, Some(elapsed));
+
+        if let Some(interval) = task.task.schedule.interval() {
+            if elapsed > interval {
+                self.record_overrun(&task, elapsed, interval);
+            }
+        }
+
+        match result {
+            Ok(result) => (Fired::Ran, Some((task, result))),
+            Err(payload) => {
+                if !self.keep_after_panic(task.id, payload) {
+                    self.record_drop(task.id);
+                    self.forget(task.id, task.task.group());
+                    return (Fired::Ran, None);
+                }
+
+                (Fired::Ran, Some((task, Ok(()))))
+            }
+        }
+    }
+
+    /// Pushes the next execution of a task which started at `fired_at` and
+    /// completed at `now`, or its retry if it failed.
+    ///
+    /// Dependents are only armed if the task actually executed.
+    fn push_next(
+        &mut self,
+        mut task: ScheduledTask<'a, T>,
+        result: Result<(), TaskError>,
+        fired: Fired,
+        fired_at: Stbi,
+        now: Stbi,
+    ) {
+        if let Err(error) = result {
+            if task.retry(now) {
+                debug!("Retrying task {} after error: {error}", task.label());
+                return self.schedule.push(task);
+            }
+
+            self.handle_error(task.label(), &error);
+        }
+
+        if fired == Fired::Ran {
+            for (id, delay, dependent) in self.dependents.remove(&task.id).into_iter().flatten() {
+                self.arm(id, delay, dependent, now);
+            }
+        }
+
+        let id = task.id;
+        let name = task.task.name.clone();
+        let group = task.task.group.clone();
+
+        let context = task.context(fired_at, now, self.clock.system_now());
+
+        match task.reschedule_with(context) {
+            Some(task) => {
+                trace!(
+                    "Rescheduled task {} in {:?}",
+                    task.label(),
+                    task.at.since(now)
+                );
+                self.schedule.push(task);
+            }
+            None => {
+                debug!("Task {} completed its schedule", Label(id, name.as_deref()));
+                self.forget(id, group.as_deref());
+            }
+        }
+    }
+
+    /// Drops the bookkeeping of a task which left the schedule.
+    fn forget(&mut self, id: TaskId, group: Option<&str>) {
+        self.stats.remove(&id);
+
+        for (dependent, _, task) in self.dependents.remove(&id).into_iter().flatten() {
+            self.record_drop(dependent);
+            self.forget(dependent, task.group());
+        }
+
+        let Some(group) = group else {
+            return;
+        };
+
+        if let Some(ids) = self.groups.get_mut(group) {
+            ids.remove(&id);
+
+            if ids.is_empty() {
+                self.groups.remove(group);
+            }
+        }
+    }
+
+    fn record_stats(
+        &mut self,
+        task: &ScheduledTask<'a, T>,
+        lateness: Duration,
+        duration: Option<Duration>,
+    ) {
+        let stats = self
+            .stats
+            .entry(task.id)
+            .or_insert_with(|| TaskStats::named(task.task.name.clone()));
+        stats.record(lateness, duration);
+
+        if lateness > self.grace_period {
+            stats.overdue += 1;
+            debug!(
+                "Task {} is {lateness:?} overdue, continuing with {:?}",
+                task.label(),
+                task.missed_tick_behavior()
+            );
+        }
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record_fire(task.id, lateness);
+
+            if let Some(duration) = duration {
+                recorder.record_duration(task.id, duration);
+            }
+        }
+    }
+
+    fn record_drop(&mut self, id: TaskId) {
+        self.dropped += 1;
+
+        if let Some(recorder) = &self.recorder {
+            recorder.record_drop(id);
+        }
+    }
+
+    fn record_overrun(
+        &mut self,
+        task: &ScheduledTask<'a, T>,
+        elapsed: Duration,
+        interval: Duration,
+    ) {
+        warn!(
+            "Task {} took {elapsed:?}, longer than its interval of {interval:?}",
+            task.label()
+        );
+
+        if let Some(stats) = self.stats.get_mut(&task.id) {
+            stats.overruns += 1;
+        }
+
+        if let Some(on_overrun) = &mut self.on_overrun {
+            on_overrun(&task.id, elapsed, interval);
+        }
+    }
+
+    fn record_timeout(&mut self, id: TaskId, elapsed: Duration) {
+        warn!("Task {id:?} is running for {elapsed:?}, longer than its timeout");
+
+        if let Some(on_timeout) = &mut self.on_timeout {
+            on_timeout(&id, elapsed);
+        }
+
+        self.cancel(id);
+    }
+
+    fn handle_error(&mut self, task: Label<'_>, error: &TaskError) {
+        warn!("Task {task} failed: {error}");
+
+        if let Some(handler) = &mut self.error_handler {
+            handler(task.0, error);
+        }
+    }
+}
+
+impl<'a, C: Clock, T: Threading> Extend<Task<'a, T>> for Scheduler<'a, C, T> {
+    /// Schedules the additional tasks relative to the same instant.
+    fn extend<I: IntoIterator<Item = Task<'a, T>>>(&mut self, tasks: I) {
+        let now = self.clock.now();
+        let system_now = self.clock.system_now();
+
+        for task in tasks {
+            self.insert(task, now, system_now);
+        }
+    }
+}
+
+impl<'a, C, T: Threading> IntoIterator for Scheduler<'a, C, T> {
+    type Item = ScheduledTask<'a, T>;
+    type IntoIter = IntoIter<'a, T>;
+
+    /// Iterates over the scheduled tasks in the order they are due, followed
+    /// by the paused tasks.
+    fn into_iter(self) -> Self::IntoIter {
+        let mut paused: Vec<_> = self.paused.into_values().collect();
+        paused.sort_by_key(|task| task.id.0);
+
+        IntoIter {
+            schedule: self.schedule,
+            cancelled: self.cancelled,
+            paused: paused.into_iter(),
+        }
+    }
+}
+
+/// The tasks of a [`Scheduler`] in the order they are due, followed by the
+/// paused tasks.
+pub struct IntoIter<'a, T: Threading = Concurrent> {
+    schedule: Queue<'a, T>,
+    cancelled: HashSet<TaskId>,
+    paused: std::vec::IntoIter<ScheduledTask<'a, T>>,
+}
+
+impl<'a, T: Threading> Iterator for IntoIter<'a, T> {
+    type Item = ScheduledTask<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(task) = self.schedule.pop() {
+            if !self.cancelled.remove(&task.id) {
+                return Some(task);
+            }
+        }
+
+        self.paused.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.schedule.len() - self.cancelled.len() + self.paused.len();
+        (len, Some(len))
+    }
+}
+
+impl<T: Threading> ExactSizeIterator for IntoIter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        println,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use super::*;
+
+    #[test]
+    #[ignore = "runs forever, its recurring tasks never complete"]
+    fn simple_schedule() {
+        let tasks = vec![
+            Schedule::Once(Some(Duration::from_secs(5))).with(|| {
+                println!("Delayed hello world");
+            }),
+            Schedule::Once(None).with(|| {
+                println!("Instant hello world");
+            }),
+            Schedule::Every(Duration::from_millis(125)).with(|| {
+                println!("I am annoying");
             }),
             Schedule::Every(Duration::from_millis(125)).with(|| {
                 println!("I am annoying too");
@@ -207,23 +3625,2485 @@ mod tests {
                 println!("I am annoying thrice");
             }),
             Schedule::Counted {
-                interval: Duration::from_millis(13),
-                count: 10,
+                interval: Duration::from_millis(13),
+                count: 10,
+            }
+            .with(|| {
+                println!("I will only be annoying 10 times");
+            }),
+            Schedule::Checked {
+                interval: Duration::from_secs(1),
+                check: Box::new(|| std::path::Path::new("Cargo.toml").exists()),
+            }
+            .with(|| {
+                println!("I will only be annoying if Cargo.toml exists in the pwd");
+            }),
+        ];
+
+        let scheduler = Scheduler::with_tasks(tasks);
+
+        scheduler.run();
+    }
+
+    #[test]
+    fn at_maps_onto_monotonic_timeline() {
+        let now = Stbi::now();
+        let system_now = SystemTime::now();
+        let ahead = Duration::from_secs(10);
+
+        let rng = &mut Rng::new(0);
+
+        let future = Schedule::At(system_now + ahead);
+        assert_eq!(future.first_at(now, system_now, rng), now + ahead);
+
+        let past = Schedule::At(system_now - ahead);
+        assert_eq!(past.first_at(now, system_now, rng), now);
+        assert!(past.reschedule().is_none());
+    }
+
+    /// A mock clock which records how long it was asked to sleep.
+    #[derive(Clone, Default)]
+    struct RecordingClock {
+        clock: MockClock,
+        sleeps: Arc<Mutex<Vec<Duration>>>,
+    }
+
+    impl Clock for RecordingClock {
+        fn now(&self) -> Stbi {
+            self.clock.now()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.sleeps.lock().unwrap().push(duration);
+            self.clock.sleep(duration);
+        }
+
+        fn system_now(&self) -> SystemTime {
+            self.clock.system_now()
+        }
+    }
+
+    #[test]
+    fn sleeps_are_split_into_slices() {
+        let clock = RecordingClock::default();
+        let task = Schedule::Once(Some(Duration::from_secs(1))).with(|| {});
+
+        Scheduler::with_clock(clock.clone(), vec![task])
+            .with_sleep_slice(Duration::from_millis(300))
+            .run();
+
+        let millis = Duration::from_millis;
+        assert_eq!(
+            *clock.sleeps.lock().unwrap(),
+            vec![millis(300), millis(300), millis(300), millis(100)]
+        );
+
+        // Without tasks the scheduler returns instead of waking up repeatedly.
+        Scheduler::with_clock(clock.clone(), Vec::new()).run();
+        assert_eq!(clock.sleeps.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn due_tasks_run_without_sleeping() {
+        let clock = RecordingClock::default();
+        let sleeps = Arc::new(Mutex::new(Vec::new()));
+
+        let task = |delay| {
+            let clock = clock.clone();
+            let sleeps = sleeps.clone();
+            Schedule::Once(Some(delay)).with(move || {
+                sleeps
+                    .lock()
+                    .unwrap()
+                    .push(clock.sleeps.lock().unwrap().len());
+                clock.clock.advance(Duration::from_millis(1));
+            })
+        };
+
+        // The last task becomes due while the others execute.
+        let tasks = vec![
+            task(Duration::ZERO),
+            task(Duration::ZERO),
+            task(Duration::ZERO),
+            task(Duration::from_millis(2)),
+        ];
+        Scheduler::with_clock(clock.clone(), tasks).run();
+
+        assert_eq!(*sleeps.lock().unwrap(), vec![0; 4]);
+        assert!(clock.sleeps.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn mock_clock_runs_in_virtual_time() {
+        let clock = MockClock::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let record = |name: &'static str| {
+            let clock = clock.clone();
+            let fired = fired.clone();
+            move || fired.lock().unwrap().push((name, clock.elapsed()))
+        };
+
+        let tasks = vec![
+            Schedule::Once(Some(Duration::from_secs(5))).with(record("once")),
+            Schedule::Counted {
+                interval: Duration::from_secs(2),
+                count: 3,
+            }
+            .with(record("counted")),
+        ];
+
+        Scheduler::with_clock(clock.clone(), tasks).run();
+
+        assert_eq!(
+            *fired.lock().unwrap(),
+            vec![
+                ("counted", Duration::from_secs(2)),
+                ("counted", Duration::from_secs(4)),
+                ("once", Duration::from_secs(5)),
+                ("counted", Duration::from_secs(6)),
+            ]
+        );
+        assert_eq!(clock.elapsed(), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn panicking_task_does_not_stop_scheduler() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let panics = Arc::new(AtomicUsize::new(0));
+
+        let counted = |f: fn()| {
+            let runs = runs.clone();
+            Schedule::Counted {
+                interval: Duration::from_secs(1),
+                count: 3,
+            }
+            .with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+                f();
+            })
+        };
+
+        let tasks = vec![counted(|| panic!("Task failed")), counted(|| {})];
+
+        let handler_panics = panics.clone();
+        Scheduler::with_clock(MockClock::new(), tasks)
+            .with_panic_handler(move |_, payload| {
+                assert_eq!(payload.downcast_ref::<&str>(), Some(&"Task failed"));
+                handler_panics.fetch_add(1, Ordering::SeqCst);
+                PanicAction::Drop
+            })
+            .run();
+
+        assert_eq!(panics.load(Ordering::SeqCst), 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn run_pending_only_fires_due_tasks() {
+        let clock = MockClock::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let count = || {
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+
+        let mut scheduler = Scheduler::with_clock(
+            clock.clone(),
+            vec![
+                Schedule::Once(None).with(count()),
+                Schedule::Once(None).with(count()),
+                Schedule::Once(Some(Duration::from_secs(3))).with(count()),
+            ],
+        );
+
+        assert_eq!(scheduler.run_pending(), Some(Duration::from_secs(3)));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(scheduler.run_pending(), Some(Duration::from_secs(2)));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        clock.advance(Duration::from_secs(2));
+        assert_eq!(scheduler.run_pending(), None);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn once_function_fires_once() {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let message = String::from("consumed");
+
+        let task = {
+            let messages = messages.clone();
+            Schedule::Every(Duration::from_secs(1)).with_once(move || {
+                messages.lock().unwrap().push(message);
+            })
+        };
+
+        Scheduler::with_clock(MockClock::new(), vec![task]).run();
+
+        assert_eq!(*messages.lock().unwrap(), vec![String::from("consumed")]);
+    }
+
+    #[test]
+    fn zero_interval_is_clamped() {
+        let clock = MockClock::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::Every(Duration::ZERO).with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), vec![task]);
+
+        assert_eq!(scheduler.run_pending(), Some(DEFAULT_MIN_INTERVAL));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        clock.advance(DEFAULT_MIN_INTERVAL);
+        assert_eq!(scheduler.run_pending(), Some(DEFAULT_MIN_INTERVAL));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        let mut scheduler = scheduler.with_min_interval(Duration::from_secs(1));
+        clock.advance(DEFAULT_MIN_INTERVAL);
+
+        assert_eq!(scheduler.run_pending(), Some(Duration::from_secs(1)));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn jittered_tasks_are_spread_out() {
+        let clock = MockClock::new();
+        let interval = Duration::from_secs(10);
+        let jitter = Duration::from_secs(1);
+
+        let tasks = (0..100)
+            .map(|_| Schedule::EveryJittered { interval, jitter }.with(|| {}))
+            .collect();
+
+        let start = clock.now();
+        let scheduler = Scheduler::with_clock(clock, tasks);
+
+        let mut offsets: Vec<_> = scheduler
+            .schedule
+            .into_vec()
+            .into_iter()
+            .filter_map(|task| task.reschedule(start))
+            .map(|task| task.at.since(start))
+            .collect();
+
+        assert!(
+            offsets
+                .iter()
+                .all(|offset| *offset >= (interval - jitter) * 2
+                    && *offset <= (interval + jitter) * 2)
+        );
+
+        offsets.sort();
+        offsets.dedup();
+        assert!(offsets.len() > 90);
+    }
+
+    #[test]
+    fn random_intervals_stay_in_range() {
+        let min = Duration::from_secs(5);
+        let max = Duration::from_secs(8);
+        assert!(Schedule::random_interval(max, min).is_none());
+
+        let clock = MockClock::new();
+        let tasks = (0..100)
+            .map(|_| Schedule::random_interval(min, max).unwrap().with(|| {}))
+            .collect();
+
+        let start = clock.now();
+        let scheduler = Scheduler::with_clock(clock, tasks);
+
+        let mut delays: Vec<_> = scheduler
+            .schedule
+            .into_vec()
+            .into_iter()
+            .inspect(|task| assert!((min..=max).contains(&task.at.since(start))))
+            .filter_map(|task| {
+                let first = task.at;
+                let next = task.reschedule(first)?;
+                Some(next.at.since(first))
+            })
+            .collect();
+
+        assert!(delays.iter().all(|delay| (min..=max).contains(delay)));
+
+        delays.sort();
+        delays.dedup();
+        assert!(delays.len() > 90);
+    }
+
+    #[test]
+    fn seeded_schedulers_are_reproducible() {
+        let second = Duration::from_secs(1);
+
+        let run = |seed| {
+            let clock = MockClock::new();
+            let fired = Arc::new(Mutex::new(Vec::new()));
+            let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new()).with_seed(seed);
+
+            for schedule in [
+                Schedule::RandomInterval {
+                    min: second,
+                    max: second * 10,
+                },
+                Schedule::EveryJittered {
+                    interval: second * 5,
+                    jitter: second,
+                },
+            ] {
+                let (clock, fired) = (clock.clone(), fired.clone());
+                scheduler.add(schedule.with(move || fired.lock().unwrap().push(clock.elapsed())));
+            }
+
+            scheduler.run_n(20);
+            let fired = fired.lock().unwrap().clone();
+            fired
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn replaced_closures_keep_the_timing() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let log = |version| {
+            let fired = fired.clone();
+            Box::new(move || fired.lock().unwrap().push(version)) as TaskFunction
+        };
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        let id = scheduler.add(Schedule::Every(second * 2).with_boxed(log(1)));
+
+        clock.advance(second * 2);
+        scheduler.run_pending();
+        clock.advance(second);
+
+        let next = scheduler.next_fire(id);
+        assert!(scheduler.replace_closure(id, log(2)));
+        assert_eq!(scheduler.next_fire(id), next);
+
+        clock.advance(second);
+        scheduler.run_pending();
+        assert_eq!(*fired.lock().unwrap(), [1, 2]);
+
+        scheduler.cancel(id);
+        assert!(!scheduler.replace_closure(id, log(3)));
+    }
+
+    #[test]
+    fn constructors_create_their_variants() {
+        let second = Duration::from_secs(1);
+        let debug = |schedule: Schedule| format!("{schedule:?}");
+
+        assert_eq!(
+            debug(Schedule::once(second)),
+            debug(Schedule::Once(Some(second)))
+        );
+        assert_eq!(debug(Schedule::immediately()), debug(Schedule::Once(None)));
+        assert_eq!(
+            debug(Schedule::every(second)),
+            debug(Schedule::Every(second))
+        );
+        assert_eq!(
+            debug(Schedule::counted(3, second)),
+            debug(Schedule::Counted {
+                interval: second,
+                count: 3
+            })
+        );
+    }
+
+    #[test]
+    fn durations_are_borrowed_from_the_schedule() {
+        let second = Duration::from_secs(1);
+
+        assert_eq!(Schedule::Once(None).as_duration(), &Duration::ZERO);
+        assert_eq!(Schedule::Every(second).as_duration(), &second);
+        assert_eq!(
+            Schedule::RandomInterval {
+                min: second,
+                max: second * 2,
+            }
+            .as_duration(),
+            &second
+        );
+        assert_eq!(
+            Schedule::At(SystemTime::now()).as_duration(),
+            &Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn counted_runs_exactly_count_times() {
+        for count in [0, 1, 7] {
+            let runs = Arc::new(AtomicUsize::new(0));
+
+            let task = {
+                let runs = runs.clone();
+                Schedule::Counted {
+                    interval: Duration::from_secs(1),
+                    count,
+                }
+                .with(move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                })
+            };
+
+            Scheduler::with_clock(MockClock::new(), vec![task]).run();
+
+            assert_eq!(runs.load(Ordering::SeqCst), count);
+        }
+    }
+
+    #[test]
+    fn counted_or_until_stops_at_the_deadline() {
+        let clock = MockClock::new();
+        let interval = Duration::from_secs(2);
+
+        let run = |count, until| {
+            let runs = Arc::new(AtomicUsize::new(0));
+            let task = {
+                let runs = runs.clone();
+                Schedule::CountedOrUntil {
+                    interval,
+                    count,
+                    until,
+                }
+                .with(move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                })
+            };
+
+            Scheduler::with_clock(clock.clone(), vec![task]).run();
+            runs.load(Ordering::SeqCst)
+        };
+
+        // The deadline is hit before the count runs out.
+        assert_eq!(run(10, clock.now() + Duration::from_secs(7)), 3);
+        // An execution exactly at the deadline still happens.
+        assert_eq!(run(10, clock.now() + Duration::from_secs(6)), 3);
+        assert_eq!(run(2, clock.now() + Duration::from_secs(60)), 2);
+    }
+
+    #[test]
+    fn remaining_matches_future_executions() {
+        let clock = MockClock::new();
+        let interval = Duration::from_secs(1);
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        let id = scheduler.add(Schedule::Counted { interval, count: 3 }.with(|| {}));
+        let every = scheduler.add(Schedule::Every(interval).with(|| {}));
+
+        assert_eq!(scheduler.remaining(every), None);
+
+        for remaining in (1..=3).rev() {
+            assert_eq!(scheduler.remaining(id), Some(remaining));
+            clock.advance(interval);
+            scheduler.run_pending();
+        }
+
+        assert_eq!(scheduler.remaining(id), None);
+    }
+
+    #[test]
+    fn tasks_are_scheduled_relative_to_the_epoch() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let epoch = clock.now();
+        clock.advance(second * 3);
+
+        let mut scheduler = Scheduler::with_clock_at(
+            clock.clone(),
+            vec![
+                Schedule::FixedRate(second).with({
+                    let runs = runs.clone();
+                    move || {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                    }
+                }),
+                Schedule::Once(Some(second * 5)).with(|| {}),
+            ],
+            epoch,
+        );
+
+        assert_eq!(scheduler.run_pending(), Some(second));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        clock.advance(second * 2);
+        assert_eq!(scheduler.run_pending(), Some(second));
+        assert_eq!(scheduler.len(), 1);
+    }
+
+    #[test]
+    fn fire_now_leaves_the_schedule_untouched() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let id = scheduler.add(
+            Schedule::Counted {
+                interval: second * 10,
+                count: 2,
+            }
+            .with({
+                let runs = runs.clone();
+                move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }
+            }),
+        );
+        let once = scheduler.add(Schedule::Once(Some(second * 10)).with_once(|| {}));
+
+        clock.advance(second * 4);
+        assert!(scheduler.fire_now(id));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(scheduler.time_until_next(), Some(second * 6));
+
+        assert!(scheduler.fire_now(once));
+        assert!(!scheduler.fire_now(once));
+
+        scheduler.run_n(2);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn overdue_tasks_continue_by_their_missed_tick_behavior() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new())
+            .with_grace_period(Duration::from_millis(100));
+
+        let ids: Vec<_> = [
+            MissedTickBehavior::Burst,
+            MissedTickBehavior::Skip,
+            MissedTickBehavior::Delay,
+        ]
+        .into_iter()
+        .map(|behavior| scheduler.add(Schedule::Every(second).with(|| {}).missed_ticks(behavior)))
+        .collect();
+        let on_time = scheduler.add(Schedule::Every(second * 4).with(|| {}));
+
+        clock.advance(Duration::from_millis(4050));
+        scheduler.run_pending();
+
+        let stats = scheduler.stats();
+        let runs: Vec<_> = ids.iter().map(|id| stats[id].runs).collect();
+        assert_eq!(runs, [4, 1, 1]);
+
+        let overdue: Vec<_> = ids.iter().map(|id| stats[id].overdue).collect();
+        assert_eq!(overdue, [3, 1, 1]);
+        assert_eq!(stats[&on_time].overdue, 0);
+
+        let next: HashMap<_, _> = scheduler
+            .next_wakeups(4)
+            .into_iter()
+            .map(|info| (info.id, info.at.since(clock.now())))
+            .collect();
+        let next: Vec<_> = ids.iter().map(|id| next[id]).collect();
+        assert_eq!(
+            next,
+            [
+                Duration::from_millis(950),
+                Duration::from_millis(950),
+                second
+            ]
+        );
+    }
+
+    #[test]
+    fn capacity_is_reserved_and_shrunk() {
+        let mut scheduler =
+            Scheduler::with_capacity(64).with_min_interval(Duration::from_millis(5));
+        assert!(scheduler.capacity() >= 64);
+
+        for _ in 0..3 {
+            scheduler.add(Schedule::Every(Duration::from_secs(1)).with(|| {}));
+        }
+
+        scheduler.shrink_to_fit();
+        assert!((3..64).contains(&scheduler.capacity()));
+
+        scheduler.reserve(100);
+        assert!(scheduler.capacity() >= 103);
+    }
+
+    #[test]
+    fn next_fire_is_overridden() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let every = scheduler.add(Schedule::Every(second * 5).with(|| {}));
+        let start = clock.now();
+        assert_eq!(scheduler.next_fire(every), Some(start + second * 5));
+
+        assert!(scheduler.set_next_fire(every, start + second * 12));
+        assert_eq!(scheduler.time_until_next(), Some(second * 12));
+
+        clock.advance(second * 12);
+        assert_eq!(scheduler.run_pending(), Some(second * 5));
+        assert_eq!(scheduler.next_fire(every), Some(start + second * 17));
+
+        scheduler.cancel(every);
+        assert_eq!(scheduler.next_fire(every), None);
+        assert!(!scheduler.set_next_fire(every, start));
+    }
+
+    #[test]
+    fn merged_tasks_keep_their_timing() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let mut first = Scheduler::with_clock(clock.clone(), Vec::new());
+        let mut other = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let kept = first.add(Schedule::Every(second * 2).with(|| {}));
+        let every = other.add(Schedule::Every(second * 3).with(|| {}).in_group("other"));
+        let paused = other.add(Schedule::Every(second).with(|| {}).in_group("other"));
+        let cancelled = other.add(Schedule::Every(second).with(|| {}));
+
+        clock.advance(second);
+        other.run_pending();
+        other.pause(paused);
+        other.cancel(cancelled);
+        let ids = first.merge(other);
+
+        assert_eq!(ids.len(), 2);
+        assert_eq!(first.stats().len(), 1);
+        assert_eq!(first.stats()[&ids[&paused]].runs, 1);
+        assert!(first.is_paused(ids[&paused]));
+        assert_eq!(first.next_fire(ids[&every]), Some(clock.now() + second * 2));
+        assert_eq!(first.cancel_group("other"), 2);
+        assert_eq!(first.next_wakeups(2)[0].id, kept);
+    }
+
+    #[test]
+    fn merging_drops_the_dependents_of_cancelled_tasks() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let mut first = Scheduler::with_clock(clock.clone(), Vec::new());
+        let mut other = Scheduler::with_clock(clock.clone(), Vec::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+        let counting = |runs: &Arc<AtomicUsize>| {
+            let runs = runs.clone();
+            Schedule::Once(None).with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        let cancelled = other.add(Schedule::Every(second).with(|| {}));
+        let waiting = other.add_after(cancelled, second, counting(&runs));
+        other.add_after(waiting, second, counting(&runs));
+        other.cancel(cancelled);
+        let ids = first.merge(other);
+
+        assert!(ids.is_empty());
+        assert!(first.dependents.is_empty());
+        assert_eq!(first.dropped, 2);
+
+        clock.advance(second * 5);
+        first.run_pending();
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn tasks_only_execute_while_their_condition_holds() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let enabled = Arc::new(AtomicBool::new(false));
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let task = Schedule::Counted {
+            interval: second,
+            count: 3,
+        }
+        .with({
+            let runs = runs.clone();
+            move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .when({
+            let enabled = enabled.clone();
+            move || enabled.load(Ordering::SeqCst)
+        });
+        let mut scheduler = Scheduler::with_clock(clock.clone(), vec![task]);
+
+        clock.advance(second);
+        assert_eq!(scheduler.run_pending(), Some(second));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        enabled.store(true, Ordering::SeqCst);
+        scheduler.run();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn short_waits_are_spun() {
+        let clock = MockClock::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let task = {
+            let (clock, fired) = (clock.clone(), fired.clone());
+            Schedule::Counted {
+                interval: Duration::from_millis(5),
+                count: 3,
+            }
+            .with(move || fired.lock().unwrap().push(clock.elapsed().as_millis()))
+        };
+
+        Scheduler::with_clock(clock.clone(), vec![task])
+            .with_spin_threshold(Duration::from_millis(2))
+            .run();
+
+        assert_eq!(*fired.lock().unwrap(), [5, 10, 15]);
+    }
+
+    #[test]
+    #[ignore = "measures real timing, run with `cargo test -- --ignored`"]
+    fn spinning_tightens_lateness() {
+        let mean_lateness = |scheduler: Scheduler| {
+            let mut scheduler = scheduler;
+            let id = scheduler.add(Schedule::Every(Duration::from_millis(5)).with(|| {}));
+            scheduler.run_n(100);
+            scheduler.stats()[&id].mean_lateness()
+        };
+
+        let slept = mean_lateness(Scheduler::new());
+        let spun = mean_lateness(Scheduler::new().with_spin_threshold(Duration::from_millis(2)));
+        println!("mean lateness slept: {slept:?}, spun: {spun:?}");
+
+        assert!(spun <= slept);
+        assert!(spun < Duration::from_millis(1));
+    }
+
+    #[test]
+    fn run_until_stops_at_deadline() {
+        let clock = MockClock::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::Every(Duration::from_secs(2)).with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), vec![task]);
+
+        scheduler.run_until(clock.now() + Duration::from_secs(5));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(clock.elapsed(), Duration::from_secs(5));
+
+        scheduler.run_until(clock.now() + Duration::from_secs(1));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(clock.elapsed(), Duration::from_secs(6));
+    }
+
+    #[test]
+    fn fixed_rate_and_fixed_delay_differ_for_slow_tasks() {
+        let fire_times = |schedule: Schedule| {
+            let clock = MockClock::new();
+            let fired = Arc::new(Mutex::new(Vec::new()));
+
+            let task = {
+                let clock = clock.clone();
+                let fired = fired.clone();
+                schedule.with(move || {
+                    fired.lock().unwrap().push(clock.elapsed().as_secs());
+                    clock.advance(Duration::from_secs(3));
+                })
+            };
+
+            let mut scheduler = Scheduler::with_clock(clock.clone(), vec![task]);
+            scheduler.run_until(clock.now() + Duration::from_secs(12));
+            drop(scheduler);
+
+            Arc::try_unwrap(fired).unwrap().into_inner().unwrap()
+        };
+
+        let interval = Duration::from_secs(2);
+        assert_eq!(fire_times(Schedule::FixedRate(interval)), vec![2, 5, 8, 11]);
+        assert_eq!(fire_times(Schedule::FixedDelay(interval)), vec![2, 7, 12]);
+        assert_eq!(fire_times(Schedule::Every(interval)), vec![2, 6, 10]);
+    }
+
+    #[test]
+    fn dynamic_schedule_backs_off() {
+        let clock = MockClock::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let task = {
+            let clock = clock.clone();
+            let fired = fired.clone();
+            Schedule::dynamic(Duration::from_secs(1), |runs| {
+                (runs < 4).then(|| Duration::from_secs(1 << runs))
+            })
+            .with(move || fired.lock().unwrap().push(clock.elapsed().as_secs()))
+        };
+
+        Scheduler::with_clock(clock.clone(), vec![task]).run();
+
+        assert_eq!(*fired.lock().unwrap(), vec![1, 3, 7, 15]);
+    }
+
+    #[test]
+    fn shutdown_finishes_current_task() {
+        let clock = MockClock::new();
+        let token = ShutdownToken::new();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let task = {
+            let token = token.clone();
+            let runs = runs.clone();
+            Schedule::Every(Duration::from_secs(1)).with(move || {
+                if runs.fetch_add(1, Ordering::SeqCst) == 2 {
+                    token.shutdown();
+                }
+            })
+        };
+
+        let tasks = vec![task, Schedule::Every(Duration::from_secs(10)).with(|| {})];
+        Scheduler::with_clock(clock.clone(), tasks).run_with_shutdown(token);
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert_eq!(clock.elapsed(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn shutdown_interrupts_long_sleep() {
+        let token = ShutdownToken::new();
+        let scheduler =
+            Scheduler::with_tasks(vec![
+                Schedule::Once(Some(Duration::from_secs(3600))).with(|| {})
+            ]);
+
+        let stopper = {
+            let token = token.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                token.shutdown();
+            })
+        };
+
+        let start = Stbi::now();
+        scheduler.run_with_shutdown(token);
+
+        assert!(Stbi::now().since(start) < Duration::from_secs(1));
+        stopper.join().unwrap();
+    }
+
+    #[test]
+    fn workers_execute_tasks_in_parallel() {
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let task = || {
+            let barrier = barrier.clone();
+            let runs = runs.clone();
+            Schedule::Once(None).with(move || {
+                barrier.wait();
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+
+        Scheduler::with_tasks(vec![task(), task()])
+            .with_workers(2)
+            .run();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn dispatches_are_bounded_by_max_in_flight() {
+        let (release, gate) = std::sync::mpsc::channel();
+        let gate = Arc::new(Mutex::new(gate));
+
+        let task = || {
+            let gate = gate.clone();
+            Schedule::Once(None).with(move || gate.lock().unwrap().recv().unwrap())
+        };
+
+        let mut scheduler = Scheduler::with_tasks(vec![task(), task()])
+            .with_workers(2)
+            .with_max_in_flight(1);
+
+        assert_eq!(scheduler.run_pending(), Some(IN_FLIGHT_POLL_INTERVAL));
+        assert_eq!(scheduler.len(), 1);
+
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+        scheduler.run();
+    }
+
+    #[test]
+    fn overlapping_executions_are_skipped_or_delayed() {
+        for policy in [OverlapPolicy::Skip, OverlapPolicy::Delay] {
+            let (release, gate) = std::sync::mpsc::channel::<()>();
+            let mut scheduler = Scheduler::new().with_workers(2);
+
+            let id = scheduler.add(
+                Schedule::Every(Duration::from_millis(5))
+                    .with(move || {
+                        let _ = gate.recv();
+                    })
+                    .no_overlap(policy),
+            );
+
+            let mut ran = 0;
+            for _ in 0..10 {
+                ran += scheduler.tick(Stbi::now()).ran;
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            assert_eq!(scheduler.stats()[&id].runs, 1);
+            assert_eq!(ran, 1);
+
+            drop(release);
+            while scheduler.stats()[&id].runs == 1 {
+                scheduler.run_pending();
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    #[test]
+    fn stuck_tasks_time_out() {
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let timeouts = Arc::new(Mutex::new(Vec::new()));
+
+        let mut scheduler = {
+            let timeouts = timeouts.clone();
+            Scheduler::new()
+                .with_workers(1)
+                .on_timeout(move |id, elapsed| timeouts.lock().unwrap().push((*id, elapsed)))
+        };
+
+        let timeout = Duration::from_millis(20);
+        let id = scheduler.add(
+            Schedule::Every(Duration::from_millis(5))
+                .with(move || {
+                    let _ = gate.recv();
+                })
+                .timeout(timeout),
+        );
+
+        while timeouts.lock().unwrap().is_empty() {
+            scheduler.run_pending();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(scheduler.run_pending(), None);
+        drop(release);
+
+        // Executions which piled up behind the stuck one time out as well.
+        let timeouts = timeouts.lock().unwrap();
+        assert!(timeouts
+            .iter()
+            .all(|&(timed_out, elapsed)| timed_out == id && elapsed >= timeout));
+    }
+
+    #[test]
+    fn overlapping_executions_time_out_separately() {
+        let second = Duration::from_secs(1);
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let timeouts = Arc::new(Mutex::new(Vec::new()));
+        let clock = MockClock::new();
+
+        let mut scheduler = {
+            let timeouts = timeouts.clone();
+            Scheduler::with_clock(clock.clone(), Vec::new())
+                .with_workers(2)
+                .on_timeout(move |id, elapsed| timeouts.lock().unwrap().push((*id, elapsed)))
+        };
+
+        let id = scheduler.add(
+            Schedule::Every(second)
+                .with(move || {
+                    let _ = gate.recv();
+                })
+                .timeout(second * 2),
+        );
+
+        for _ in 0..4 {
+            clock.advance(second);
+            scheduler.run_pending();
+        }
+
+        assert_eq!(*timeouts.lock().unwrap(), vec![(id, second * 2); 2]);
+        drop(release);
+    }
+
+    #[test]
+    fn dropping_detaches_stuck_workers() {
+        let second = Duration::from_secs(1);
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let (started, running) = std::sync::mpsc::channel();
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new()).with_workers(1);
+
+        scheduler.add(
+            Schedule::Once(None)
+                .with(move || {
+                    let _ = started.send(());
+                    let _ = gate.recv();
+                })
+                .timeout(second),
+        );
+
+        scheduler.run_pending();
+        running.recv().unwrap();
+        clock.advance(second);
+        scheduler.run_pending();
+
+        // Returns although the execution on the worker never completes.
+        drop(scheduler);
+        drop(release);
+    }
+
+    #[test]
+    fn huge_durations_do_not_overflow() {
+        let mut scheduler = Scheduler::with_tasks(vec![
+            Schedule::Once(Some(Duration::MAX)).with(|| {}),
+            Schedule::Every(Duration::MAX).with(|| {}),
+        ]);
+
+        assert_eq!(scheduler.run_pending(), Some(WALL_CLOCK_SLICE));
+        assert!(scheduler.time_until_next().unwrap() >= FAR_FUTURE / 2);
+    }
+
+    #[test]
+    fn time_until_next_skips_cancelled_tasks() {
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        assert_eq!(scheduler.time_until_next(), None);
+
+        let first = scheduler.add(Schedule::Once(Some(Duration::from_secs(1))).with(|| {}));
+        scheduler.add(Schedule::Once(Some(Duration::from_secs(4))).with(|| {}));
+        assert_eq!(scheduler.time_until_next(), Some(Duration::from_secs(1)));
+
+        scheduler.cancel(first);
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(scheduler.time_until_next(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn push_grows_empty_scheduler() {
+        let mut scheduler = Scheduler::new();
+        assert!(scheduler.is_empty());
+
+        scheduler.push(Schedule::Once(None).with(|| {}));
+        scheduler.push(Schedule::Every(Duration::from_secs(1)).with(|| {}));
+        assert_eq!(scheduler.len(), 2);
+        assert!(!scheduler.is_empty());
+    }
+
+    #[test]
+    fn schedulers_collect_and_extend_tasks() {
+        let mut scheduler: Scheduler = (0..3).map(|_| Schedule::Once(None).with(|| {})).collect();
+        assert_eq!(scheduler.len(), 3);
+
+        scheduler.extend([Schedule::Every(Duration::from_secs(60)).with(|| {})]);
+        assert_eq!(scheduler.len(), 4);
+
+        scheduler.run_pending();
+        assert_eq!(scheduler.len(), 1);
+        assert!(Scheduler::default().is_empty());
+    }
+
+    #[test]
+    fn cron_reschedules_to_next_occurrence() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        assert!(Schedule::cron("* * *").is_err());
+        scheduler.add(Schedule::Cron(String::from("0 0 31 2 *")).with(|| {}));
+        assert!(scheduler.is_empty());
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::cron("* * * * *").unwrap().with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+        scheduler.add(task);
+
+        let first = scheduler.time_until_next().unwrap();
+        assert!(first <= Duration::from_secs(60));
+
+        clock.advance(first);
+        scheduler.run_pending();
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        assert_eq!(scheduler.time_until_next(), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn calendar_tasks_recur_daily_and_weekly() {
+        const HOUR: Duration = Duration::from_secs(60 * 60);
+
+        let clock = MockClock::new();
+        // 2024-01-01 08:00 UTC, a Monday.
+        clock.set_system_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1_704_096_000));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let nine_am = TimeOfDay::new(9, 0, 0).unwrap();
+        let daily = scheduler.add(Schedule::DailyUtc { at: nine_am }.with(|| {}));
+        let weekly = Schedule::WeeklyUtc {
+            weekday: Weekday::Tuesday,
+            at: nine_am,
+        };
+        let weekly = scheduler.add(weekly.with(|| {}));
+        assert_eq!(scheduler.time_until_next(), Some(HOUR));
+
+        scheduler.run_until(clock.now() + 2 * HOUR);
+        assert_eq!(scheduler.stats()[&daily].runs, 1);
+        assert_eq!(scheduler.time_until_next(), Some(23 * HOUR));
+
+        scheduler.run_until(clock.now() + 24 * HOUR);
+        assert_eq!(scheduler.stats()[&daily].runs, 2);
+        assert_eq!(scheduler.stats()[&weekly].runs, 1);
+        assert_eq!(
+            scheduler.peek_next().map(|next| next.at.since(clock.now())),
+            Some(23 * HOUR)
+        );
+    }
+
+    /// A schedule of every kind, failing to compile once a kind is added.
+    fn schedule_of_kind(kind: ScheduleKind) -> Schedule {
+        let second = Duration::from_secs(1);
+        let zone = || -> Arc<dyn TimeZone> { Arc::new(FixedOffset::UTC) };
+
+        match kind {
+            ScheduleKind::Once => Schedule::Once(None),
+            ScheduleKind::OnceAt => Schedule::OnceAt(Instant::now()),
+            ScheduleKind::At => Schedule::At(SystemTime::UNIX_EPOCH),
+            ScheduleKind::Every => Schedule::Every(second),
+            ScheduleKind::FixedRate => Schedule::FixedRate(second),
+            ScheduleKind::FixedDelay => Schedule::FixedDelay(second),
+            ScheduleKind::EveryAligned => Schedule::EveryAligned(second),
+            ScheduleKind::EveryWithOffset => Schedule::EveryWithOffset {
+                interval: second,
+                offset: second,
+            },
+            ScheduleKind::EveryJittered => Schedule::EveryJittered {
+                interval: second,
+                jitter: second,
+            },
+            ScheduleKind::RandomInterval => Schedule::RandomInterval {
+                min: second,
+                max: second,
+            },
+            ScheduleKind::Counted => Schedule::counted(1, second),
+            ScheduleKind::CountedOrUntil => Schedule::CountedOrUntil {
+                interval: second,
+                count: 1,
+                until: Stbi::now(),
+            },
+            ScheduleKind::Checked => Schedule::Checked {
+                interval: second,
+                check: Box::new(|| true),
+            },
+            ScheduleKind::Dynamic => Schedule::dynamic(second, |_| None),
+            ScheduleKind::SelfDirected => Schedule::SelfDirected {
+                delay: second,
+                next: None,
+            },
+            ScheduleKind::Cron => Schedule::Cron(String::from("* * * * *")),
+            ScheduleKind::CronIn => Schedule::CronIn {
+                expression: String::from("* * * * *"),
+                zone: zone(),
+            },
+            ScheduleKind::DailyUtc => Schedule::DailyUtc {
+                at: TimeOfDay::MIDNIGHT,
+            },
+            ScheduleKind::WeeklyUtc => Schedule::WeeklyUtc {
+                weekday: Weekday::Monday,
+                at: TimeOfDay::MIDNIGHT,
+            },
+            ScheduleKind::Daily => Schedule::Daily {
+                at: TimeOfDay::MIDNIGHT,
+                zone: zone(),
+            },
+            ScheduleKind::Weekly => Schedule::Weekly {
+                weekday: Weekday::Monday,
+                at: TimeOfDay::MIDNIGHT,
+                zone: zone(),
+            },
+            ScheduleKind::Then => Schedule::Once(None).then(Schedule::Never),
+            ScheduleKind::Never => Schedule::Never,
+        }
+    }
+
+    #[test]
+    fn schedules_map_to_their_kind() {
+        let kinds = [
+            ScheduleKind::Once,
+            ScheduleKind::OnceAt,
+            ScheduleKind::At,
+            ScheduleKind::Every,
+            ScheduleKind::FixedRate,
+            ScheduleKind::FixedDelay,
+            ScheduleKind::EveryAligned,
+            ScheduleKind::EveryWithOffset,
+            ScheduleKind::EveryJittered,
+            ScheduleKind::RandomInterval,
+            ScheduleKind::Counted,
+            ScheduleKind::CountedOrUntil,
+            ScheduleKind::Checked,
+            ScheduleKind::Dynamic,
+            ScheduleKind::SelfDirected,
+            ScheduleKind::Cron,
+            ScheduleKind::CronIn,
+            ScheduleKind::DailyUtc,
+            ScheduleKind::WeeklyUtc,
+            ScheduleKind::Daily,
+            ScheduleKind::Weekly,
+            ScheduleKind::Then,
+            ScheduleKind::Never,
+        ];
+
+        for kind in kinds {
+            let schedule = schedule_of_kind(kind);
+            assert_eq!(schedule.kind(), kind);
+            assert_eq!(kind.name(), format!("{kind:?}"));
+            assert!(format!("{schedule:?}").starts_with(kind.name()));
+        }
+    }
+
+    #[test]
+    fn calendar_tasks_follow_their_time_zone() {
+        const HOUR: Duration = Duration::from_secs(60 * 60);
+
+        let clock = MockClock::new();
+        // 2024-01-01 08:00 UTC, a Monday, 09:00 an hour east of UTC.
+        clock.set_system_time(SystemTime::UNIX_EPOCH + Duration::from_secs(1_704_096_000));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        let zone = FixedOffset::east(60 * 60).unwrap();
+        let ten_am = TimeOfDay::new(10, 0, 0).unwrap();
+
+        let daily = Schedule::Daily {
+            at: ten_am,
+            zone: Arc::new(zone),
+        };
+        let daily = scheduler.add(daily.with(|| {}));
+        let weekly = Schedule::Weekly {
+            weekday: Weekday::Monday,
+            at: ten_am,
+            zone: Arc::new(zone),
+        };
+        let weekly = scheduler.add(weekly.with(|| {}));
+        let cron = scheduler.add(Schedule::cron_in("0 10 * * *", zone).unwrap().with(|| {}));
+        assert!(Schedule::cron_in("0 10", zone).is_err());
+        assert_eq!(scheduler.time_until_next(), Some(HOUR));
+
+        scheduler.run_until(clock.now() + 2 * HOUR);
+        for id in [daily, weekly, cron] {
+            assert_eq!(scheduler.stats()[&id].runs, 1);
+        }
+        assert_eq!(scheduler.next_fire(daily), Some(clock.now() + 23 * HOUR));
+        assert_eq!(scheduler.next_fire(cron), Some(clock.now() + 23 * HOUR));
+        assert_eq!(
+            scheduler.next_fire(weekly),
+            Some(clock.now() + (7 * 24 - 1) * HOUR)
+        );
+    }
+
+    #[test]
+    fn chained_schedules_run_one_after_another() {
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let schedule = Schedule::Once(Some(Duration::from_secs(10)))
+            .then(Schedule::Every(Duration::from_secs(1)));
+        let id = scheduler.add(schedule.with(|| {}));
+        assert_eq!(
+            scheduler.get(id).map(|task| task.task().schedule().kind()),
+            Some(ScheduleKind::Then)
+        );
+        assert_eq!(scheduler.time_until_next(), Some(Duration::from_secs(10)));
+
+        scheduler.run_until(clock.now() + Duration::from_secs(10));
+        assert_eq!(scheduler.stats()[&id].runs, 1);
+        assert_eq!(scheduler.time_until_next(), Some(Duration::from_secs(1)));
+        assert_eq!(
+            scheduler.get(id).map(|task| task.task().schedule().kind()),
+            Some(ScheduleKind::Every)
+        );
+
+        scheduler.run_until(clock.now() + Duration::from_secs(3));
+        assert_eq!(scheduler.stats()[&id].runs, 4);
+
+        // An exhausted second stage is not started.
+        let schedule = Schedule::Once(None).then(Schedule::Counted {
+            interval: Duration::from_secs(1),
+            count: 0,
+        });
+        assert!(schedule.reschedule().is_none());
+    }
+
+    #[test]
+    fn wall_clock_tasks_follow_backward_jumps() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let clock = MockClock::new();
+        let start = clock.system_now();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let at = {
+            let fired = fired.clone();
+            Schedule::At(start + Duration::from_secs(120)).with(move || {
+                fired.store(true, Ordering::SeqCst);
+            })
+        };
+        scheduler.add(at);
+        let minutely = scheduler.add(Schedule::cron("* * * * *").unwrap().with(|| {}));
+
+        scheduler.run_until(clock.now() + Duration::from_secs(60));
+        assert_eq!(scheduler.stats()[&minutely].runs, 1);
+
+        clock.set_system_time(clock.system_now() - Duration::from_secs(60 * 60));
+
+        // The cron task continues with the next minute of the new time instead
+        // of waiting for the previous occurrence to come around again.
+        assert!(scheduler.run_pending().unwrap() <= Duration::from_secs(60));
+        scheduler.cancel(minutely);
+
+        // The one-shot waits until the wall-clock reaches its time again.
+        scheduler.run();
+        assert!(fired.load(Ordering::SeqCst));
+        assert_eq!(clock.elapsed(), Duration::from_secs(60 + 60 * 60 + 60));
+    }
+
+    #[test]
+    fn wall_clock_tasks_follow_gradual_adjustments() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let id = scheduler.add(Schedule::At(clock.system_now() + second * 10).with(|| {}));
+        let every = scheduler.add(Schedule::Every(second * 20).with(|| {}));
+        // Within the tolerance of a jump, only the next task is mapped again.
+        clock.set_system_time(clock.system_now() + Duration::from_millis(500));
+        assert_eq!(
+            scheduler.run_pending(),
+            Some(second * 10 - Duration::from_millis(500))
+        );
+        assert_eq!(
+            scheduler.next_fire(id),
+            Some(clock.now() + Duration::from_millis(9500))
+        );
+        assert_eq!(scheduler.next_fire(every), Some(clock.now() + second * 20));
+    }
+
+    #[test]
+    fn wall_clock_tasks_survive_suspend() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let clock = MockClock::new();
+        let hour = Duration::from_secs(60 * 60);
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let at = {
+            let fired = fired.clone();
+            Schedule::At(clock.system_now() + hour * 2).with(move || {
+                fired.store(true, Ordering::SeqCst);
+            })
+        };
+        scheduler.add(at);
+        assert_eq!(scheduler.run_pending(), Some(WALL_CLOCK_SLICE));
+
+        // While suspended the monotonic clock stands still.
+        clock.set_system_time(clock.system_now() + hour * 3);
+
+        assert_eq!(scheduler.run_pending(), None);
+        assert!(fired.load(Ordering::SeqCst));
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn offset_tasks_are_staggered() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+        let start = clock.now();
+        let runs = Arc::new(Mutex::new(Vec::new()));
+
+        let task = |schedule: Schedule, name| {
+            let runs = runs.clone();
+            let clock = clock.clone();
+            schedule.with(move || runs.lock().unwrap().push((name, clock.now().since(start))))
+        };
+
+        let mut scheduler = Scheduler::with_clock(
+            clock.clone(),
+            vec![
+                task(Schedule::Every(second * 10), "every"),
+                task(
+                    Schedule::EveryWithOffset {
+                        interval: second * 10,
+                        offset: second * 5,
+                    },
+                    "offset",
+                ),
+            ],
+        );
+        scheduler.run_until(start + second * 25);
+
+        assert_eq!(
+            *runs.lock().unwrap(),
+            [
+                ("offset", second * 5),
+                ("every", second * 10),
+                ("offset", second * 15),
+                ("every", second * 20),
+                ("offset", second * 25),
+            ]
+        );
+    }
+
+    #[test]
+    fn aligned_tasks_fire_on_epoch_grid() {
+        let minute = Duration::from_secs(60);
+        let ten_o_clock = UNIX_EPOCH + Duration::from_secs(10 * 60 * 60);
+
+        assert_eq!(
+            Schedule::until_aligned(minute, ten_o_clock + Duration::from_secs(17)),
+            Duration::from_secs(43)
+        );
+        assert_eq!(Schedule::until_aligned(minute, ten_o_clock), Duration::ZERO);
+
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        scheduler.add(Schedule::EveryAligned(minute).with(|| {}));
+
+        let first = scheduler.time_until_next().unwrap();
+        assert!(first <= minute);
+
+        clock.advance(first + Duration::from_secs(1));
+        scheduler.run_pending();
+        assert_eq!(
+            scheduler.time_until_next(),
+            Some(minute - Duration::from_secs(1))
+        );
+    }
+
+    #[test]
+    fn simultaneous_tasks_fire_in_insertion_order() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let tasks = (0..10)
+            .map(|index| {
+                let fired = fired.clone();
+                Schedule::Once(None).with(move || fired.lock().unwrap().push(index))
+            })
+            .collect();
+
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), tasks);
+        scheduler.run_pending();
+
+        assert_eq!(*fired.lock().unwrap(), (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn higher_priority_runs_first() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let task = |name, priority| {
+            let fired = fired.clone();
+            Schedule::Once(None)
+                .with(move || fired.lock().unwrap().push(name))
+                .priority(priority)
+        };
+
+        let tasks = vec![task("low", -1), task("default", 0), task("high", 10)];
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), tasks);
+        scheduler.run_pending();
+
+        assert_eq!(*fired.lock().unwrap(), vec!["high", "default", "low"]);
+    }
+
+    #[test]
+    fn tick_steps_through_virtual_time() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(clock, Vec::new());
+        scheduler.add(Schedule::Every(Duration::from_secs(1)).with(|| {}));
+
+        let tick = |scheduler: &mut Scheduler<_>, millis| {
+            scheduler.tick(start + Duration::from_millis(millis))
+        };
+
+        assert_eq!(
+            tick(&mut scheduler, 500),
+            TickOutcome {
+                ran: 0,
+                next: Some(Duration::from_millis(500)),
+            }
+        );
+        assert_eq!(
+            tick(&mut scheduler, 1000),
+            TickOutcome {
+                ran: 1,
+                next: Some(Duration::from_secs(1)),
+            }
+        );
+        // The execution due at 2s runs late, the one at 3s is skipped.
+        assert_eq!(
+            tick(&mut scheduler, 3500),
+            TickOutcome {
+                ran: 1,
+                next: Some(Duration::from_millis(500)),
+            }
+        );
+
+        scheduler.pause_all();
+        assert_eq!(
+            tick(&mut scheduler, 5000),
+            TickOutcome {
+                ran: 0,
+                next: Some(scheduler.sleep_slice),
+            }
+        );
+    }
+
+    #[test]
+    fn drained_tasks_run_at_the_callers_discretion() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(clock, Vec::new());
+
+        for schedule in [
+            Schedule::Every(Duration::from_secs(1)),
+            Schedule::Once(None),
+        ] {
+            let runs = runs.clone();
+            scheduler.add(schedule.with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }));
+        }
+
+        let due = scheduler.drain_due(start);
+        assert_eq!(due.len(), 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+
+        let due = scheduler.drain_due(start + Duration::from_secs(1));
+        assert_eq!(due.len(), 1);
+        assert_eq!(scheduler.len(), 1);
+        assert_eq!(
+            scheduler.tick(start + Duration::from_secs(1)).next,
+            Some(Duration::from_secs(1))
+        );
+
+        for mut task in due {
+            task.call().unwrap();
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // The scheduled task keeps the shared function.
+        scheduler.tick(start + Duration::from_secs(2));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn tasks_move_between_schedulers() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let counted = scheduler.add(
+            Schedule::Counted {
+                interval: Duration::from_secs(2),
+                count: 3,
             }
-            .with(|| {
-                println!("I will only be annoying 10 times");
-            }),
-            Schedule::Checked {
-                interval: Duration::from_secs(1),
-                check: Box::new(|| std::path::Path::new("Cargo.toml").exists()),
+            .with(|| {}),
+        );
+        let once = scheduler.add(Schedule::Once(Some(Duration::from_secs(3))).with(|| {}));
+        let cancelled = scheduler.add(Schedule::Once(None).with(|| {}));
+        scheduler.cancel(cancelled);
+        scheduler.tick(start + Duration::from_secs(2));
+
+        let scheduled: Vec<_> = scheduler
+            .into_iter()
+            .map(|task| (task.id(), task.at().since(start)))
+            .collect();
+        assert_eq!(
+            scheduled,
+            vec![
+                (once, Duration::from_secs(3)),
+                (counted, Duration::from_secs(4))
+            ]
+        );
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        scheduler.add(
+            Schedule::Counted {
+                interval: Duration::from_secs(2),
+                count: 3,
             }
-            .with(|| {
-                println!("I will only be annoying if Cargo.toml exists in the pwd");
+            .with(|| {}),
+        );
+        scheduler.tick(start + Duration::from_secs(2));
+
+        let moved = Scheduler::with_clock(clock, scheduler.into_tasks());
+        assert_eq!(moved.remaining(TaskId(0)), Some(2));
+    }
+
+    #[test]
+    fn never_tasks_are_held_without_running() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::Never.with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+        scheduler.add(task);
+        scheduler.add(Schedule::Once(Some(Duration::from_secs(1))).with(|| {}));
+
+        scheduler.run_until(clock.now() + Duration::from_secs(60 * 60));
+        assert_eq!(runs.load(Ordering::SeqCst), 0);
+        assert_eq!(scheduler.len(), 1);
+        assert_eq!(scheduler.run_pending(), Some(WALL_CLOCK_SLICE));
+    }
+
+    #[test]
+    fn tasks_are_retained_or_cleared() {
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), Vec::new());
+        let every = Duration::from_secs(1);
+
+        let kept = scheduler.add(Schedule::Every(every).with(|| {}).in_group("kept"));
+        scheduler.add(Schedule::Every(every).with(|| {}).in_group("client"));
+        scheduler.add(Schedule::Every(every).with(|| {}).in_group("client"));
+        let cancelled = scheduler.add(Schedule::Every(every).with(|| {}));
+        scheduler.cancel(cancelled);
+
+        scheduler.retain(|task| task.task().group() != Some("client"));
+        assert_eq!(scheduler.len(), 1);
+        assert_eq!(scheduler.cancel_group("client"), 0);
+        assert!(scheduler.get(kept).is_some());
+
+        scheduler.clear();
+        assert!(scheduler.is_empty());
+        assert_eq!(scheduler.run_pending(), None);
+    }
+
+    #[test]
+    fn paused_tasks_resume_from_now() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let every = scheduler.add(Schedule::Every(Duration::from_secs(2)).with(|| {}));
+        scheduler.run_until(start + Duration::from_secs(2));
+        assert!(scheduler.pause(every));
+        assert!(!scheduler.pause(every));
+        assert!(scheduler.is_paused(every));
+        assert!(scheduler.is_empty());
+
+        clock.advance(Duration::from_secs(7));
+        assert_eq!(scheduler.run_pending(), None);
+        assert_eq!(scheduler.stats()[&every].runs, 1);
+
+        assert!(scheduler.resume(every));
+        assert!(!scheduler.resume(every));
+        assert_eq!(scheduler.time_until_next(), Some(Duration::from_secs(2)));
+
+        scheduler.run_until(start + Duration::from_secs(11));
+        assert_eq!(scheduler.stats()[&every].runs, 2);
+
+        scheduler.pause(every);
+        assert!(scheduler.cancel(every));
+        assert!(!scheduler.resume(every));
+    }
+
+    #[test]
+    fn dependents_run_after_their_dependency() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let second = Duration::from_secs(1);
+        let runs = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let record = |name| {
+            let runs = runs.clone();
+            let clock = clock.clone();
+            move || runs.lock().unwrap().push((name, clock.now().since(start)))
+        };
+
+        let download = {
+            let clock = clock.clone();
+            scheduler.add(Schedule::Once(Some(second)).with(move || clock.advance(second * 2)))
+        };
+        let unpack = scheduler.add_after(
+            download,
+            second * 3,
+            Schedule::Once(None).with(record("unpack")),
+        );
+        scheduler.add_after(
+            unpack,
+            Duration::ZERO,
+            Schedule::Once(None).with(record("install")),
+        );
+
+        let never = scheduler.add(Schedule::Never.with(|| {}));
+        scheduler.add_after(
+            never,
+            Duration::ZERO,
+            Schedule::Once(None).with(record("dropped")),
+        );
+
+        assert_eq!(scheduler.len(), 2);
+        assert!(scheduler.cancel(never));
+        scheduler.run();
+
+        assert_eq!(
+            *runs.lock().unwrap(),
+            [("unpack", second * 6), ("install", second * 6)]
+        );
+    }
+
+    #[test]
+    fn dependents_wait_for_an_actual_execution() {
+        let clock = MockClock::new();
+        let second = Duration::from_secs(1);
+        let enabled = Arc::new(AtomicBool::new(false));
+        let armed = Arc::new(AtomicBool::new(false));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let dependency = scheduler.add(Schedule::Every(second).with(|| {}).when({
+            let enabled = enabled.clone();
+            move || enabled.load(Ordering::SeqCst)
+        }));
+        scheduler.add_after(
+            dependency,
+            Duration::ZERO,
+            Schedule::Once(None).with({
+                let armed = armed.clone();
+                move || armed.store(true, Ordering::SeqCst)
             }),
+        );
+
+        scheduler.run_until(clock.now() + second * 3);
+        assert!(!armed.load(Ordering::SeqCst));
+        assert_eq!(scheduler.len(), 1);
+
+        enabled.store(true, Ordering::SeqCst);
+        scheduler.run_until(clock.now() + second);
+        assert!(armed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn catch_up_policies_replay_or_coalesce_missed_executions() {
+        let hour = Duration::from_secs(60 * 60);
+
+        let runs = |policy| {
+            let clock = MockClock::new();
+            clock.set_system_time(UNIX_EPOCH + hour / 2);
+
+            let mut scheduler = Scheduler::with_clock(
+                clock.clone(),
+                vec![
+                    Schedule::cron("0 * * * *").unwrap().with(|| {}),
+                    Schedule::Every(hour).with(|| {}),
+                    Schedule::FixedRate(hour).with(|| {}),
+                ],
+            )
+            .with_catch_up(policy);
+
+            clock.advance(hour * 5 / 2);
+            scheduler.run_pending();
+
+            let mut runs: Vec<_> = scheduler
+                .stats()
+                .iter()
+                .map(|(id, stats)| (id.0, stats.runs))
+                .collect();
+            runs.sort_unstable();
+            runs.into_iter().map(|(_, runs)| runs).collect::<Vec<_>>()
+        };
+
+        assert_eq!(runs(CatchUpPolicy::CatchUp), [3, 2, 2]);
+        assert_eq!(runs(CatchUpPolicy::Realtime), [1, 1, 1]);
+    }
+
+    #[test]
+    fn run_n_stops_after_n_executions() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(
+            clock.clone(),
+            vec![Schedule::Every(Duration::from_secs(1)).with(|| {})],
+        );
+
+        assert_eq!(scheduler.run_n(3).fired, 3);
+        assert_eq!(clock.now().since(start), Duration::from_secs(3));
+        assert_eq!(scheduler.run_n(0).fired, 0);
+
+        let mut scheduler = Scheduler::with_clock(clock, vec![Schedule::Once(None).with(|| {})]);
+        assert_eq!(scheduler.run_n(5).fired, 1);
+        assert!(scheduler.is_empty());
+    }
+
+    #[test]
+    fn runs_are_summarized() {
+        let second = Duration::from_secs(1);
+        let clock = MockClock::new();
+
+        let tasks = vec![
+            Schedule::Counted {
+                interval: second,
+                count: 3,
+            }
+            .with(|| {}),
+            Schedule::Every(second * 2).with(|| panic!("dropped")),
+            // Skipped executions are not counted as fired.
+            Schedule::Counted {
+                interval: second,
+                count: 2,
+            }
+            .with(|| {})
+            .when(|| false),
         ];
+        let scheduler =
+            Scheduler::with_clock(clock, tasks).with_panic_handler(|_, _| PanicAction::Drop);
 
-        let scheduler = Scheduler::with_tasks(tasks);
+        assert_eq!(
+            scheduler.run(),
+            RunSummary {
+                fired: 4,
+                dropped: 1,
+                elapsed: second * 3,
+            }
+        );
+    }
+
+    #[test]
+    fn frozen_schedulers_do_not_run() {
+        let clock = MockClock::new();
+        let tasks = vec![Schedule::Every(Duration::from_secs(1)).with(|| {})];
+        let mut scheduler = Scheduler::with_clock(clock.clone(), tasks);
+        scheduler.pause_all();
+
+        assert_eq!(scheduler.run_n(3).fired, 0);
+        assert_eq!(
+            scheduler.run(),
+            RunSummary {
+                fired: 0,
+                dropped: 0,
+                elapsed: Duration::ZERO,
+            }
+        );
+    }
+
+    #[test]
+    fn pausing_all_tasks_freezes_their_timing() {
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let every = scheduler.add(Schedule::Every(Duration::from_secs(3)).with(|| {}));
+        let once = scheduler.add(Schedule::Once(Some(Duration::from_secs(5))).with(|| {}));
+
+        clock.advance(Duration::from_secs(1));
+        scheduler.pause_all();
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(scheduler.run_pending(), Some(scheduler.sleep_slice));
+        assert!(scheduler.stats().is_empty());
+
+        scheduler.resume_all();
+        assert_eq!(scheduler.time_until_next(), Some(Duration::from_secs(2)));
+
+        clock.advance(Duration::from_secs(2));
+        scheduler.run_pending();
+        assert_eq!(scheduler.stats()[&every].runs, 1);
+        assert!(!scheduler.stats().contains_key(&once));
+
+        clock.advance(Duration::from_secs(2));
+        scheduler.run_pending();
+        assert_eq!(scheduler.len(), 1);
+    }
+
+    #[test]
+    fn tasks_can_be_rescheduled_by_id() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let task = {
+            let runs = runs.clone();
+            Schedule::Never.with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+        let id = scheduler.add(task);
+        scheduler.add(Schedule::Every(Duration::from_secs(1)).with(|| {}));
+
+        clock.advance(Duration::from_secs(10));
+        assert!(scheduler.reschedule_task(id, Schedule::Every(Duration::from_secs(5))));
+        assert_eq!(scheduler.time_until_next(), Some(Duration::ZERO));
+
+        scheduler.run_until(clock.now() + Duration::from_secs(12));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        assert!(scheduler.reschedule_task(
+            id,
+            Schedule::Counted {
+                interval: Duration::from_secs(1),
+                count: 0,
+            }
+        ));
+        assert_eq!(scheduler.len(), 1);
+        assert!(!scheduler.reschedule_task(id, Schedule::Once(None)));
+    }
+
+    #[test]
+    fn checked_since_detects_reversed_instants() {
+        let earlier = Stbi::now();
+        let later = earlier + Duration::from_secs(1);
+
+        assert_eq!(later.checked_since(earlier), Some(Duration::from_secs(1)));
+        assert_eq!(earlier.checked_since(earlier), Some(Duration::ZERO));
+        assert_eq!(earlier.checked_since(later), None);
+        assert_eq!(earlier.since(later), Duration::ZERO);
+    }
+
+    #[test]
+    fn stbi_converts_to_instants() {
+        let instant = Instant::now();
+        let stbi = Stbi::from_instant(instant);
+
+        assert_eq!(stbi.into_instant(), instant);
+        assert_eq!((stbi + Duration::from_secs(60)).elapsed(), Duration::ZERO);
+        assert!(
+            Stbi::from_instant(instant - Duration::from_millis(1)).elapsed()
+                >= Duration::from_millis(1)
+        );
+    }
+
+    #[test]
+    fn waker_is_woken_by_sooner_tasks() {
+        struct Counter(AtomicUsize);
+
+        impl std::task::Wake for Counter {
+            fn wake(self: Arc<Self>) {
+                self.wake_by_ref();
+            }
+
+            fn wake_by_ref(self: &Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let second = Duration::from_secs(1);
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), Vec::new());
+        scheduler.register_waker(Waker::from(counter.clone()));
+
+        scheduler.add(Schedule::Every(second * 10).with(|| {}));
+        scheduler.add(Schedule::Once(Some(second * 20)).with(|| {}));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+        scheduler.add(Schedule::Once(Some(second)).with(|| {}));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+
+        scheduler.clear_waker();
+        scheduler.add(Schedule::Once(None).with(|| {}));
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn stbi_is_displayed_relative_to_now() {
+        let minute = Duration::from_secs(60);
+        let now = Stbi::now();
+
+        assert_eq!(format!("{:.0}", now + minute), "+60s");
+        let debug = format!("{:?}", now + minute);
+        assert!(
+            debug.starts_with("Stbi(+") && debug.ends_with("s)"),
+            "{debug}"
+        );
+
+        if let Some(earlier) = Instant::now().checked_sub(minute) {
+            assert_eq!(format!("{:.0}", Stbi::from_instant(earlier)), "-60s");
+        }
+    }
+
+    #[test]
+    fn once_at_fires_at_the_instant() {
+        let clock = MockClock::new();
+        let deadline = Instant::from(clock.now()) + Duration::from_millis(1500);
+        let fired = Arc::new(Mutex::new(None));
+
+        let task = {
+            let clock = clock.clone();
+            let fired = fired.clone();
+            Schedule::OnceAt(deadline).with(move || *fired.lock().unwrap() = Some(clock.now()))
+        };
+
+        Scheduler::with_clock(clock.clone(), vec![task]).run();
+        assert_eq!(*fired.lock().unwrap(), Some(Stbi::from(deadline)));
+    }
+
+    #[test]
+    fn named_tasks_are_debuggable() {
+        let task = Schedule::Checked {
+            interval: Duration::from_secs(1),
+            check: Box::new(|| true),
+        }
+        .with(|| {})
+        .named("cleanup");
+
+        let debug = format!("{task:?}");
+        assert!(debug.starts_with("Task { name: Some(\"cleanup\"), "));
+        assert!(debug.contains("schedule: Checked { interval: 1s, .. }"));
+
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), vec![task]);
+        clock.advance(Duration::from_secs(1));
+        scheduler.run_pending();
+
+        assert_eq!(
+            scheduler.stats()[&TaskId(0)].name.as_deref(),
+            Some("cleanup")
+        );
+    }
+
+    #[test]
+    fn missed_ticks_follow_their_behavior() {
+        let tick_after_missing = |behavior| {
+            let clock = MockClock::new();
+            let start = clock.now();
+            let task = Schedule::Every(Duration::from_secs(2)).with(|| {});
+            let mut scheduler = Scheduler::with_clock(clock, vec![task.missed_ticks(behavior)]);
+
+            // The execution due at 2s runs late, the one at 4s is missed.
+            let outcome = scheduler.tick(start + Duration::from_secs(5));
+            (outcome.ran, outcome.next.unwrap().as_secs())
+        };
+
+        assert_eq!(tick_after_missing(MissedTickBehavior::Burst), (2, 1));
+        assert_eq!(tick_after_missing(MissedTickBehavior::Skip), (1, 1));
+        assert_eq!(tick_after_missing(MissedTickBehavior::Delay), (1, 2));
+    }
+
+    #[test]
+    fn immediate_tasks_fire_when_added() {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let task = {
+            let fired = fired.clone();
+            Schedule::Every(Duration::from_secs(5))
+                .with(move || fired.lock().unwrap().push(Instant::now()))
+                .fire_immediately()
+        };
+
+        let added = Instant::now();
+        let mut scheduler = Scheduler::with_tasks(vec![task]);
+        let next = scheduler.run_pending().unwrap();
+
+        let fired = fired.lock().unwrap();
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].duration_since(added) < Duration::from_millis(1));
+        assert!(next > Duration::from_millis(4999) && next <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn groups_are_cancelled_together() {
+        let runs = Arc::new(AtomicUsize::new(0));
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        for group in ["session", "session", "other"] {
+            let runs = runs.clone();
+            let task = Schedule::Every(Duration::from_secs(1)).with(move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            });
+            scheduler.add(task.in_group(group));
+        }
+        scheduler.add(Schedule::Once(None).with(|| {}).in_group("session"));
+        scheduler.run_pending();
+
+        assert_eq!(scheduler.cancel_group("session"), 2);
+        assert_eq!(scheduler.cancel_group("session"), 0);
+        assert_eq!(scheduler.cancel_group("missing"), 0);
+
+        scheduler.run_until(clock.now() + Duration::from_secs(3));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+        assert!(!scheduler.groups.contains_key("session"));
+    }
+
+    #[test]
+    fn peek_next_describes_the_next_task() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(clock, Vec::new());
+        assert_eq!(scheduler.peek_next(), None);
+
+        scheduler.add(Schedule::Every(Duration::from_secs(5)).with(|| {}));
+        let reindex = scheduler.add(
+            Schedule::Once(Some(Duration::from_secs(4)))
+                .with(|| {})
+                .named("reindex"),
+        );
+
+        assert_eq!(
+            scheduler.peek_next(),
+            Some(NextInfo {
+                id: reindex,
+                name: Some(Arc::from("reindex")),
+                at: start + Duration::from_secs(4),
+                kind: ScheduleKind::Once,
+            })
+        );
+
+        scheduler.cancel(reindex);
+        assert_eq!(
+            scheduler.peek_next().map(|next| next.kind),
+            Some(ScheduleKind::Every)
+        );
+    }
+
+    #[test]
+    fn next_wakeups_are_ordered_by_time() {
+        let second = Duration::from_secs(1);
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), Vec::new());
+
+        let ids: Vec<_> = [3, 1, 4, 2]
+            .map(|secs| scheduler.add(Schedule::Every(second * secs).with(|| {})))
+            .into();
+        scheduler.cancel(ids[3]);
+
+        let wakeups = scheduler.next_wakeups(2);
+        assert_eq!(
+            wakeups.iter().map(|next| next.id).collect::<Vec<_>>(),
+            [ids[1], ids[0]]
+        );
+        assert_eq!(scheduler.next_wakeups(10).len(), 3);
+        assert_eq!(scheduler.len(), 3);
+    }
+
+    #[test]
+    fn rate_limit_defers_due_tasks() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler =
+            Scheduler::with_clock(clock, Vec::new()).with_rate_limit(1, Duration::from_secs(1));
+        let at = |millis| start + Duration::from_millis(millis);
+
+        let every = scheduler.add(Schedule::Every(Duration::from_millis(500)).with(|| {}));
+        scheduler.add(Schedule::Once(None).with(|| {}));
+        let deferred = scheduler.add(Schedule::Once(None).with(|| {}));
+
+        assert_eq!(scheduler.tick(at(0)).ran, 1);
+        assert_eq!(scheduler.tick(at(500)).ran, 0);
+
+        assert_eq!(scheduler.tick(at(1000)).ran, 1);
+        assert!(scheduler.get(deferred).is_none());
+        assert_eq!(scheduler.len(), 1);
+
+        // The task due at 500ms was deferred twice and continues on its grid,
+        // whose next point at 2s exceeds the limit again.
+        assert_eq!(
+            scheduler.tick(at(2000)),
+            TickOutcome {
+                ran: 1,
+                next: Some(Duration::from_secs(1)),
+            }
+        );
+        assert_eq!(
+            scheduler.stats()[&every].max_lateness,
+            Duration::from_millis(1500)
+        );
+    }
+
+    #[test]
+    fn failed_executions_are_retried() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let errors = Arc::new(Mutex::new(Vec::new()));
+
+        let task = {
+            let calls = calls.clone();
+            Schedule::Every(Duration::from_secs(10))
+                .with_result(move || match calls.fetch_add(1, Ordering::SeqCst) {
+                    0..=2 => Err("unavailable"),
+                    _ => Ok(()),
+                })
+                .retry(RetryPolicy::Fixed {
+                    delay: Duration::from_millis(100),
+                    retries: 2,
+                })
+        };
+
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = {
+            let errors = errors.clone();
+            Scheduler::with_clock(clock, vec![task]).with_error_handler(move |_, error| {
+                errors.lock().unwrap().push(error.to_string());
+            })
+        };
+
+        let mut tick = |millis| scheduler.tick(start + Duration::from_millis(millis)).next;
+
+        assert_eq!(tick(10_000), Some(Duration::from_millis(100)));
+        assert_eq!(tick(10_100), Some(Duration::from_millis(100)));
+        assert!(errors.lock().unwrap().is_empty());
+
+        // Out of retries, the task continues on its regular schedule.
+        assert_eq!(tick(10_200), Some(Duration::from_millis(9_800)));
+        assert_eq!(*errors.lock().unwrap(), vec!["unavailable"]);
+
+        assert_eq!(tick(20_000), Some(Duration::from_secs(10)));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+        assert_eq!(errors.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn callbacks_surround_executions() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let clock = MockClock::new();
+
+        let task = {
+            let clock = clock.clone();
+            let events = events.clone();
+            Schedule::Once(None).with(move || {
+                events.lock().unwrap().push(String::from("run"));
+                clock.advance(Duration::from_secs(3));
+            })
+        };
+
+        let mut scheduler = {
+            let before = events.clone();
+            let after = events.clone();
+            Scheduler::with_clock(clock, Vec::new())
+                .on_before(move |id| before.lock().unwrap().push(format!("before {id:?}")))
+                .on_after(move |id, elapsed| {
+                    after
+                        .lock()
+                        .unwrap()
+                        .push(format!("after {id:?} {}s", elapsed.as_secs()))
+                })
+        };
+
+        scheduler.add(task);
+        scheduler.run_pending();
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec!["before TaskId(0)", "run", "after TaskId(0) 3s"]
+        );
+    }
+
+    #[test]
+    fn overruns_are_reported() {
+        let overruns = Arc::new(Mutex::new(Vec::new()));
+        let clock = MockClock::new();
+
+        let task = {
+            let clock = clock.clone();
+            Schedule::Every(Duration::from_secs(1))
+                .with(move || clock.advance(Duration::from_millis(1500)))
+        };
+
+        let mut scheduler = {
+            let overruns = overruns.clone();
+            Scheduler::with_clock(clock.clone(), Vec::new()).on_overrun(
+                move |_, elapsed, interval| overruns.lock().unwrap().push((elapsed, interval)),
+            )
+        };
+        let id = scheduler.add(task);
+        scheduler.add(Schedule::Every(Duration::from_secs(10)).with(|| {}));
+
+        scheduler.run_until(clock.now() + Duration::from_secs(3));
+
+        assert_eq!(scheduler.stats()[&id].overruns, 2);
+        assert_eq!(
+            *overruns.lock().unwrap(),
+            vec![(Duration::from_millis(1500), Duration::from_secs(1)); 2]
+        );
+    }
+
+    #[test]
+    fn stats_track_lateness() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        let mut scheduler = Scheduler::with_clock(clock, Vec::new());
+        let every = scheduler.add(Schedule::Every(Duration::from_secs(1)).with(|| {}));
+        let once = scheduler.add(Schedule::Once(None).with(|| {}));
+
+        for millis in [1300, 2000, 3600] {
+            scheduler.tick(start + Duration::from_millis(millis));
+        }
+
+        let stats = scheduler.stats();
+        assert!(!stats.contains_key(&once));
+
+        let stats = &stats[&every];
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.min_lateness, Duration::ZERO);
+        assert_eq!(stats.max_lateness, Duration::from_millis(600));
+        assert_eq!(stats.mean_lateness(), Duration::from_millis(300));
+        assert_eq!(stats.last_duration, Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn scoped_scheduler_borrows_locals() {
+        let mut runs = Vec::new();
+        let interval = Duration::from_secs(1);
+
+        let scheduler = Scheduler::with_clock(
+            MockClock::new(),
+            vec![Schedule::Counted { interval, count: 3 }.with(|| runs.push(runs.len()))],
+        );
+        scheduler.run();
+
+        assert_eq!(runs, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn cancel_stops_recurring_task() {
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let mut scheduler = Scheduler::with_tasks(Vec::new());
+
+        let cancelled = scheduler.add(Schedule::Every(Duration::from_millis(1)).with(|| {
+            panic!("Cancelled task must not run");
+        }));
+
+        let counted = {
+            let runs = runs.clone();
+            scheduler.add(
+                Schedule::Counted {
+                    interval: Duration::from_millis(1),
+                    count: 3,
+                }
+                .with(move || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }),
+            )
+        };
+
+        assert_ne!(cancelled, counted);
+        assert_eq!(scheduler.len(), 2);
+        assert!(scheduler.cancel(cancelled));
+        assert!(!scheduler.cancel(cancelled));
+        assert_eq!(scheduler.len(), 1);
 
         scheduler.run();
+
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn reschedule_compensates_drift() {
+        let start = Stbi::now();
+        let interval = Duration::from_millis(100);
+
+        let mut task = ScheduledTask {
+            id: TaskId(0),
+            at: start,
+            wall: None,
+            seq: 0,
+            rng: Rng::new(0),
+            retry: None,
+            deferred: None,
+            task: Schedule::Every(interval).with(|| {
+                std::thread::sleep(Duration::from_millis(30));
+            }),
+        };
+
+        task.task.f.call().unwrap();
+        let task = task.reschedule(Stbi::now()).expect("Every reschedules");
+
+        assert_eq!(task.at, start + interval);
+    }
+
+    #[test]
+    fn reschedule_with_separates_the_execution_times() {
+        let start = Stbi::now();
+        let interval = Duration::from_millis(100);
+
+        let task = |schedule: Schedule| ScheduledTask {
+            id: TaskId(0),
+            at: start,
+            wall: None,
+            seq: 0,
+            rng: Rng::new(0),
+            retry: None,
+            deferred: None,
+            task: schedule.with(|| {}),
+        };
+        let context = RescheduleContext {
+            scheduled_at: start,
+            fired_at: start + Duration::from_millis(20),
+            completed_at: start + Duration::from_millis(50),
+            system_completed_at: SystemTime::now(),
+        };
+
+        let every = task(Schedule::Every(interval)).reschedule_with(context);
+        let delay = task(Schedule::FixedDelay(interval)).reschedule_with(context);
+
+        assert_eq!(every.map(|task| task.at), Some(start + interval));
+        assert_eq!(
+            delay.map(|task| task.at),
+            Some(context.completed_at + interval)
+        );
+
+        // Calendar schedules continue from the given wall-clock time.
+        let context = RescheduleContext {
+            system_completed_at: UNIX_EPOCH + Duration::from_secs(60 * 60),
+            ..context
+        };
+        let daily = task(Schedule::DailyUtc {
+            at: TimeOfDay::MIDNIGHT,
+        })
+        .reschedule_with(context);
+        assert_eq!(
+            daily.map(|task| task.at),
+            Some(context.completed_at + Duration::from_secs(23 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn reschedule_skips_missed_intervals() {
+        let start = Stbi::now();
+        let interval = Duration::from_millis(100);
+
+        let mut task = ScheduledTask {
+            id: TaskId(0),
+            at: start,
+            wall: None,
+            seq: 0,
+            rng: Rng::new(0),
+            retry: None,
+            deferred: None,
+            task: Schedule::Every(interval).with(|| {
+                std::thread::sleep(Duration::from_millis(130));
+            }),
+        };
+
+        task.task.f.call().unwrap();
+        let task = task.reschedule(Stbi::now()).expect("Every reschedules");
+
+        assert_eq!(task.at, start + interval * 2);
     }
 }
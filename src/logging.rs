@@ -0,0 +1,74 @@
+//! Log events through the `log` facade if the `log` feature is enabled.
+//!
+//! Without the feature the macros discard their arguments, which are still
+//! type checked.
+
+#[cfg(feature = "log")]
+pub(crate) use log::{debug, trace, warn};
+
+#[cfg(not(feature = "log"))]
+macro_rules! discard {
+    ($($arg:tt)*) => {
+        if false {
+            let _ = format_args!($($arg)*);
+        }
+    };
+}
+
+#[cfg(not(feature = "log"))]
+pub(crate) use {discard as debug, discard as trace, discard as warn};
+
+#[cfg(all(test, feature = "log"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    use crate::{MockClock, Schedule, Scheduler};
+
+    /// The events of all tests, which share the global logger.
+    static EVENTS: Mutex<Vec<(Level, String)>> = Mutex::new(Vec::new());
+
+    struct Collector;
+
+    impl Log for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            let event = (record.level(), record.args().to_string());
+            EVENTS.lock().unwrap().push(event);
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn executions_skips_and_errors_are_logged() {
+        static COLLECTOR: Collector = Collector;
+        log::set_logger(&COLLECTOR).unwrap();
+        log::set_max_level(LevelFilter::Trace);
+
+        let tasks = vec![
+            Schedule::Once(None).with(|| {}).named("logged-run"),
+            Schedule::Once(None)
+                .with(|| {})
+                .when(|| false)
+                .named("logged-skip"),
+            Schedule::Once(None)
+                .with_result(|| Err("broken"))
+                .named("logged-error"),
+        ];
+        Scheduler::with_clock(MockClock::new(), tasks).run_pending();
+
+        let logged = |level, message: &str, name: &str| {
+            EVENTS.lock().unwrap().iter().any(|(logged, event)| {
+                *logged == level && event.starts_with(message) && event.contains(name)
+            })
+        };
+        assert!(logged(Level::Debug, "Executing task", "(logged-run)"));
+        assert!(logged(Level::Debug, "Skipping task", "(logged-skip)"));
+        assert!(logged(Level::Warn, "Task", "(logged-error) failed: broken"));
+    }
+}
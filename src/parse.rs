@@ -0,0 +1,186 @@
+use std::{error::Error, fmt, str::FromStr, time::Duration};
+
+use crate::Schedule;
+
+/// An error parsing a schedule from a string, see [`Schedule::from_str`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseScheduleError {
+    /// The input does not start with `once`, `every` or a count like `10x`.
+    UnknownSchedule(String),
+    /// A duration is missing at the end of the input.
+    MissingDuration,
+    /// A duration is not a sequence of numbers with the units `ms`, `s`, `m`
+    /// or `h`, e.g. `1h30m`.
+    InvalidDuration(String),
+    /// A count is not followed by `every`, e.g. `10x once`.
+    ExpectedEvery(String),
+    /// The input continues after the schedule.
+    TrailingInput(String),
+}
+
+impl fmt::Display for ParseScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownSchedule(word) => write!(
+                f,
+                "unknown schedule `{word}`, expected `once`, `every` or a count like `10x`"
+            ),
+            Self::MissingDuration => write!(f, "expected a duration"),
+            Self::InvalidDuration(word) => write!(f, "invalid duration `{word}`"),
+            Self::ExpectedEvery(word) => {
+                write!(f, "expected `every` after the count, found `{word}`")
+            }
+            Self::TrailingInput(rest) => write!(f, "unexpected `{rest}` after the schedule"),
+        }
+    }
+}
+
+impl Error for ParseScheduleError {}
+
+impl FromStr for Schedule {
+    type Err = ParseScheduleError;
+
+    /// Parses a [`Schedule::Once`], [`Schedule::Every`] or
+    /// [`Schedule::Counted`] from `once`, `once 5m`, `every 30s` or
+    /// `10x every 1s`.
+    ///
+    /// Durations consist of numbers with the units `ms`, `s`, `m` and `h`,
+    /// e.g. `1h30m`. Words are separated by whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+
+        let schedule = match words.next() {
+            Some("once") => Self::Once(words.next().map(parse_duration).transpose()?),
+            Some("every") => Self::Every(parse_duration(expect_duration(words.next())?)?),
+            Some(word) => {
+                let count = word
+                    .strip_suffix('x')
+                    .and_then(|count| count.parse().ok())
+                    .ok_or_else(|| ParseScheduleError::UnknownSchedule(word.to_string()))?;
+
+                match words.next() {
+                    Some("every") => {}
+                    Some(word) => return Err(ParseScheduleError::ExpectedEvery(word.to_string())),
+                    None => return Err(ParseScheduleError::ExpectedEvery(String::new())),
+                }
+
+                Self::Counted {
+                    interval: parse_duration(expect_duration(words.next())?)?,
+                    count,
+                }
+            }
+            None => return Err(ParseScheduleError::UnknownSchedule(String::new())),
+        };
+
+        let rest: Vec<_> = words.collect();
+        if !rest.is_empty() {
+            return Err(ParseScheduleError::TrailingInput(rest.join(" ")));
+        }
+
+        Ok(schedule)
+    }
+}
+
+impl TryFrom<&str> for Schedule {
+    type Error = ParseScheduleError;
+
+    /// See [`Schedule::from_str`].
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+fn expect_duration(word: Option<&str>) -> Result<&str, ParseScheduleError> {
+    word.ok_or(ParseScheduleError::MissingDuration)
+}
+
+fn parse_duration(word: &str) -> Result<Duration, ParseScheduleError> {
+    let invalid = || ParseScheduleError::InvalidDuration(word.to_string());
+
+    let mut rest = word;
+    let mut total = Duration::ZERO;
+
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let part = match &rest[..unit_len] {
+            "ms" => Duration::from_millis(value),
+            "s" => Duration::from_secs(value),
+            "m" => value
+                .checked_mul(60)
+                .map(Duration::from_secs)
+                .ok_or_else(invalid)?,
+            "h" => value
+                .checked_mul(60 * 60)
+                .map(Duration::from_secs)
+                .ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit_len..];
+
+        total = total.checked_add(part).ok_or_else(invalid)?;
+    }
+
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_schedules_are_parsed() {
+        let second = Duration::from_secs(1);
+
+        let parse = |s: &str| {
+            s.parse::<Schedule>()
+                .map(|schedule| format!("{schedule:?}"))
+        };
+        let debug = |schedule: Schedule| Ok(format!("{schedule:?}"));
+
+        assert_eq!(parse("once"), debug(Schedule::Once(None)));
+        assert_eq!(parse("once 5m"), debug(Schedule::Once(Some(second * 300))));
+        assert_eq!(parse(" every  30s "), debug(Schedule::Every(second * 30)));
+        assert_eq!(
+            parse("every 1h30m"),
+            debug(Schedule::Every(second * 90 * 60))
+        );
+        assert_eq!(
+            parse("10x every 250ms"),
+            debug(Schedule::Counted {
+                interval: Duration::from_millis(250),
+                count: 10
+            })
+        );
+        assert!(Schedule::try_from("every 1s").is_ok());
+
+        assert_eq!(
+            parse("daily"),
+            Err(ParseScheduleError::UnknownSchedule("daily".into()))
+        );
+        assert_eq!(parse("every"), Err(ParseScheduleError::MissingDuration));
+        assert_eq!(
+            parse("every 5"),
+            Err(ParseScheduleError::InvalidDuration("5".into()))
+        );
+        assert_eq!(
+            parse("every 5d"),
+            Err(ParseScheduleError::InvalidDuration("5d".into()))
+        );
+        assert_eq!(
+            parse("3x once"),
+            Err(ParseScheduleError::ExpectedEvery("once".into()))
+        );
+        assert_eq!(
+            parse("once 1s now"),
+            Err(ParseScheduleError::TrailingInput("now".into()))
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{Concurrent, Job, Stbi, TaskError, TaskId, Threading};
+
+/// The way an execution on a worker failed.
+pub(crate) enum Failure {
+    Panic(Box<dyn Any + Send>),
+    Error(TaskError),
+}
+
+struct Dispatch<'a, T: Threading> {
+    id: TaskId,
+    /// The number of the execution, keying its watch if it has a timeout.
+    execution: u64,
+    job: Arc<Mutex<Job<'a, T>>>,
+}
+
+/// A dispatched execution with a timeout which did not complete yet.
+struct Watch {
+    id: TaskId,
+    dispatched: Stbi,
+    timeout: Duration,
+    reported: bool,
+    /// Whether a worker picked up the execution.
+    started: bool,
+}
+
+/// The watches of the executions, keyed by their number so that overlapping
+/// executions of a task are watched separately.
+type Running = Mutex<HashMap<u64, Watch>>;
+
+type Dispatched = Mutex<HashMap<TaskId, usize>>;
+
+/// Worker threads executing dispatched tasks.
+///
+/// Only pools of `'static` jobs can be created, the lifetime allows the
+/// scheduler to hold a pool regardless of the lifetime of its tasks.
+pub(crate) struct Pool<'a, T: Threading = Concurrent> {
+    jobs: Option<Sender<Dispatch<'a, T>>>,
+    failures: Receiver<(TaskId, Failure)>,
+    workers: Vec<JoinHandle<()>>,
+    /// The number of dispatched tasks which did not complete yet.
+    in_flight: Arc<AtomicUsize>,
+    /// The number of dispatched executions of each task which did not
+    /// complete yet.
+    dispatched: Arc<Dispatched>,
+    running: Arc<Running>,
+    next_execution: AtomicU64,
+}
+
+impl Pool<'static> {
+    pub(crate) fn new(size: usize) -> Self {
+        let (jobs, queue) = mpsc::channel();
+        let (failed, failures) = mpsc::channel();
+        let queue = Arc::new(Mutex::new(queue));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let dispatched = Arc::new(Mutex::new(HashMap::new()));
+        let running = Arc::new(Mutex::new(HashMap::new()));
+
+        let workers = (0..size.max(1))
+            .map(|index| {
+                let queue = Arc::clone(&queue);
+                let failed = failed.clone();
+                let in_flight = Arc::clone(&in_flight);
+                let dispatched = Arc::clone(&dispatched);
+                let running = Arc::clone(&running);
+
+                thread::Builder::new()
+                    .name(format!("casched-worker-{index}"))
+                    .spawn(move || Self::work(&queue, &failed, &in_flight, &dispatched, &running))
+                    .expect("Failed to spawn worker thread")
+            })
+            .collect();
+
+        Self {
+            jobs: Some(jobs),
+            failures,
+            workers,
+            in_flight,
+            dispatched,
+            running,
+            next_execution: AtomicU64::new(0),
+        }
+    }
+
+    fn work(
+        queue: &Mutex<Receiver<Dispatch<'static, Concurrent>>>,
+        failed: &Sender<(TaskId, Failure)>,
+        in_flight: &AtomicUsize,
+        dispatched: &Dispatched,
+        running: &Running,
+    ) {
+        loop {
+            let next = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+            let Ok(Dispatch { id, execution, job }) = next else {
+                return;
+            };
+
+            if let Some(watch) = lock(running).get_mut(&execution) {
+                watch.started = true;
+            }
+
+            let mut job = job.lock().unwrap_or_else(PoisonError::into_inner);
+            let result = panic::catch_unwind(AssertUnwindSafe(|| job.call()));
+            drop(job);
+
+            lock(running).remove(&execution);
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            complete(dispatched, id);
+
+            let failure = match result {
+                Ok(Ok(())) => continue,
+                Ok(Err(error)) => Failure::Error(error),
+                Err(payload) => Failure::Panic(payload),
+            };
+
+            let _ = failed.send((id, failure));
+        }
+    }
+}
+
+/// Counts an execution of the task as completed.
+fn complete(dispatched: &Dispatched, id: TaskId) {
+    let mut dispatched = dispatched.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(count) = dispatched.get_mut(&id) {
+        *count -= 1;
+
+        if *count == 0 {
+            dispatched.remove(&id);
+        }
+    }
+}
+
+fn lock(running: &Running) -> MutexGuard<'_, HashMap<u64, Watch>> {
+    running.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<'a, T: Threading> Pool<'a, T> {
+    /// Hands the task function to a worker at `now`.
+    ///
+    /// Executions which did not complete `timeout` after `now` are reported
+    /// by [`Pool::timed_out`].
+    pub(crate) fn dispatch(
+        &self,
+        id: TaskId,
+        job: &mut Job<'a, T>,
+        timeout: Option<Duration>,
+        now: Stbi,
+    ) {
+        let Some(job) = job.share() else {
+            return;
+        };
+
+        if let Some(jobs) = &self.jobs {
+            let execution = self.next_execution.fetch_add(1, Ordering::Relaxed);
+            self.in_flight.fetch_add(1, Ordering::SeqCst);
+            *self
+                .dispatched
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(id)
+                .or_default() += 1;
+
+            if let Some(timeout) = timeout {
+                let watch = Watch {
+                    id,
+                    dispatched: now,
+                    timeout,
+                    reported: false,
+                    started: false,
+                };
+                lock(&self.running).insert(execution, watch);
+            }
+
+            if jobs.send(Dispatch { id, execution, job }).is_err() {
+                lock(&self.running).remove(&execution);
+                self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                complete(&self.dispatched, id);
+            }
+        }
+    }
+
+    /// Whether an execution of the task was dispatched and did not complete
+    /// yet.
+    pub(crate) fn is_in_flight(&self, id: TaskId) -> bool {
+        self.dispatched
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains_key(&id)
+    }
+
+    /// The number of dispatched tasks which did not complete yet.
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// The executions which exceeded their timeout at `now` together with the
+    /// time since they were dispatched, each execution being reported once.
+    pub(crate) fn timed_out(&self, now: Stbi) -> Vec<(TaskId, Duration)> {
+        lock(&self.running)
+            .values_mut()
+            .filter(|watch| !watch.reported && now.since(watch.dispatched) >= watch.timeout)
+            .map(|watch| {
+                watch.reported = true;
+                (watch.id, now.since(watch.dispatched))
+            })
+            .collect()
+    }
+
+    /// Panics and errors of tasks which were caught by the workers.
+    pub(crate) fn failures(&self) -> impl Iterator<Item = (TaskId, Failure)> + '_ {
+        self.failures.try_iter()
+    }
+}
+
+impl<T: Threading> Drop for Pool<'_, T> {
+    /// Waits for all dispatched tasks to complete.
+    ///
+    /// Once the remaining workers are all occupied by executions which were
+    /// reported by [`Pool::timed_out`], they are detached instead, as those
+    /// executions might never return.
+    fn drop(&mut self) {
+        self.jobs.take();
+
+        loop {
+            let (finished, busy) = self
+                .workers
+                .drain(..)
+                .partition::<Vec<_>, _>(JoinHandle::is_finished);
+            self.workers = busy;
+
+            for worker in finished {
+                let _ = worker.join();
+            }
+
+            let stuck = lock(&self.running)
+                .values()
+                .filter(|watch| watch.reported && watch.started)
+                .count();
+
+            if self.workers.len() <= stuck {
+                self.workers.clear();
+                return;
+            }
+
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
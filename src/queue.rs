@@ -0,0 +1,474 @@
+use std::{
+    collections::{BinaryHeap, HashSet},
+    iter,
+    time::Duration,
+};
+
+use crate::{Concurrent, ScheduledTask, Stbi, TaskId, Threading};
+
+const SLOT_BITS: u32 = 6;
+const SLOTS: usize = 1 << SLOT_BITS;
+const LEVELS: usize = u64::BITS.div_ceil(SLOT_BITS) as usize;
+
+/// The pending tasks of a scheduler, ordered by their next execution.
+pub(crate) struct Queue<'a, T: Threading = Concurrent> {
+    tasks: Tasks<'a, T>,
+    /// The ids of all tasks in the queue, so lookups need not search it.
+    ids: HashSet<TaskId>,
+}
+
+enum Tasks<'a, T: Threading> {
+    Heap(BinaryHeap<ScheduledTask<'a, T>>),
+    Wheel(Box<Wheel<'a, T>>),
+}
+
+impl<'a, T: Threading> Queue<'a, T> {
+    /// Moves all tasks into a timer wheel.
+    pub(crate) fn into_wheel(mut self, origin: Stbi, resolution: Duration) -> Self {
+        let ids = std::mem::take(&mut self.ids);
+        let mut wheel = Wheel::new(origin, resolution);
+
+        for task in self.into_vec() {
+            wheel.push(task);
+        }
+
+        Self {
+            tasks: Tasks::Wheel(Box::new(wheel)),
+            ids,
+        }
+    }
+
+    pub(crate) fn push(&mut self, task: ScheduledTask<'a, T>) {
+        self.ids.insert(task.id);
+
+        match &mut self.tasks {
+            Tasks::Heap(heap) => heap.push(task),
+            Tasks::Wheel(wheel) => wheel.push(task),
+        }
+    }
+
+    /// Removes the task which is due first.
+    pub(crate) fn pop(&mut self) -> Option<ScheduledTask<'a, T>> {
+        let task = match &mut self.tasks {
+            Tasks::Heap(heap) => heap.pop(),
+            Tasks::Wheel(wheel) => wheel.pop(),
+        }?;
+
+        self.ids.remove(&task.id);
+        Some(task)
+    }
+
+    /// The task which is due first.
+    pub(crate) fn peek(&self) -> Option<&ScheduledTask<'a, T>> {
+        match &self.tasks {
+            Tasks::Heap(heap) => heap.peek(),
+            Tasks::Wheel(wheel) => wheel.peek(),
+        }
+    }
+
+    /// Whether the queue holds the task with the given id.
+    pub(crate) fn contains(&self, id: TaskId) -> bool {
+        self.ids.contains(&id)
+    }
+
+    /// The task with the given id.
+    pub(crate) fn get(&self, id: TaskId) -> Option<&ScheduledTask<'a, T>> {
+        if !self.contains(id) {
+            return None;
+        }
+
+        self.iter().find(|task| task.id == id)
+    }
+
+    /// Removes the task with the given id.
+    pub(crate) fn remove(&mut self, id: TaskId) -> Option<ScheduledTask<'a, T>> {
+        if !self.ids.remove(&id) {
+            return None;
+        }
+
+        match &mut self.tasks {
+            Tasks::Heap(heap) => remove_from_heap(heap, id),
+            Tasks::Wheel(wheel) => wheel.remove(id),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match &self.tasks {
+            Tasks::Heap(heap) => heap.len(),
+            Tasks::Wheel(wheel) => wheel.len,
+        }
+    }
+
+    /// Iterates over all tasks in no particular order.
+    pub(crate) fn iter(&self) -> Box<dyn Iterator<Item = &ScheduledTask<'a, T>> + '_> {
+        match &self.tasks {
+            Tasks::Heap(heap) => Box::new(heap.iter()),
+            Tasks::Wheel(wheel) => Box::new(wheel.iter()),
+        }
+    }
+
+    /// The number of tasks the queue holds without reallocating. Timer
+    /// wheels spread their tasks over many slots, so this is just the number
+    /// of their tasks.
+    pub(crate) fn capacity(&self) -> usize {
+        match &self.tasks {
+            Tasks::Heap(heap) => heap.capacity(),
+            Tasks::Wheel(wheel) => wheel.len,
+        }
+    }
+
+    /// Reserves space for at least `additional` more tasks. Timer wheels do
+    /// not know in advance which slots the tasks go to and reserve nothing.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.ids.reserve(additional);
+
+        if let Tasks::Heap(heap) = &mut self.tasks {
+            heap.reserve(additional);
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.ids.shrink_to_fit();
+
+        match &mut self.tasks {
+            Tasks::Heap(heap) => heap.shrink_to_fit(),
+            Tasks::Wheel(wheel) => wheel.shrink_to_fit(),
+        }
+    }
+
+    /// Removes all tasks, keeping the kind of queue.
+    pub(crate) fn drain(&mut self) -> Vec<ScheduledTask<'a, T>> {
+        self.ids.clear();
+
+        match &mut self.tasks {
+            Tasks::Heap(heap) => heap.drain().collect(),
+            Tasks::Wheel(wheel) => wheel.drain(),
+        }
+    }
+
+    pub(crate) fn into_vec(mut self) -> Vec<ScheduledTask<'a, T>> {
+        self.drain()
+    }
+}
+
+impl<T: Threading> Default for Queue<'_, T> {
+    fn default() -> Self {
+        Self {
+            tasks: Tasks::Heap(BinaryHeap::new()),
+            ids: HashSet::new(),
+        }
+    }
+}
+
+impl<'a, T: Threading> Extend<ScheduledTask<'a, T>> for Queue<'a, T> {
+    fn extend<I: IntoIterator<Item = ScheduledTask<'a, T>>>(&mut self, tasks: I) {
+        for task in tasks {
+            self.push(task);
+        }
+    }
+}
+
+fn remove_from_heap<'a, T: Threading>(
+    heap: &mut BinaryHeap<ScheduledTask<'a, T>>,
+    id: TaskId,
+) -> Option<ScheduledTask<'a, T>> {
+    let index = heap.iter().position(|task| task.id == id)?;
+    let mut tasks = std::mem::take(heap).into_vec();
+    let task = tasks.swap_remove(index);
+    *heap = tasks.into();
+    Some(task)
+}
+
+/// A hierarchical timer wheel.
+///
+/// Time is divided into ticks of `resolution` since `origin`. Each level has
+/// 64 slots, a slot of level `n` spanning `64^n` ticks. A task is stored on
+/// the lowest level whose slots distinguish its tick from the `cursor`, so
+/// the first occupied slot of the lowest occupied level holds the next task.
+/// Slots above the first level are unordered and are cascaded into lower
+/// levels once they are the next to expire.
+pub(crate) struct Wheel<'a, T: Threading = Concurrent> {
+    origin: Stbi,
+    resolution: u128,
+    /// A tick no later than the tick of any task on the levels.
+    cursor: u64,
+    /// The first level, where each slot holds tasks of a single tick.
+    ticks: Vec<BinaryHeap<ScheduledTask<'a, T>>>,
+    /// The remaining levels.
+    levels: Vec<Vec<Vec<ScheduledTask<'a, T>>>>,
+    /// Bit sets of the occupied slots of each level.
+    occupied: [u64; LEVELS],
+    /// Tasks which were added for a tick before the cursor.
+    early: BinaryHeap<ScheduledTask<'a, T>>,
+    len: usize,
+}
+
+impl<'a, T: Threading> Wheel<'a, T> {
+    fn new(origin: Stbi, resolution: Duration) -> Self {
+        Self {
+            origin,
+            resolution: resolution.as_nanos().max(1),
+            cursor: 0,
+            ticks: iter::repeat_with(BinaryHeap::new).take(SLOTS).collect(),
+            levels: iter::repeat_with(|| iter::repeat_with(Vec::new).take(SLOTS).collect())
+                .take(LEVELS - 1)
+                .collect(),
+            occupied: [0; LEVELS],
+            early: BinaryHeap::new(),
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, task: ScheduledTask<'a, T>) {
+        self.len += 1;
+
+        let tick = self.tick(task.at);
+
+        if tick < self.cursor {
+            self.early.push(task);
+            return;
+        }
+
+        let (level, slot) = self.position(tick);
+
+        match level {
+            0 => self.ticks[slot].push(task),
+            _ => self.levels[level - 1][slot].push(task),
+        }
+
+        self.occupied[level] |= 1 << slot;
+    }
+
+    fn pop(&mut self) -> Option<ScheduledTask<'a, T>> {
+        let slot = loop {
+            match self.first_slot() {
+                Some((0, slot)) => break Some(slot),
+                Some((level, slot)) => self.cascade(level, slot),
+                None => break None,
+            }
+        };
+
+        let on_wheel = slot.and_then(|slot| self.ticks[slot].peek());
+        let from_early = match (self.early.peek(), on_wheel) {
+            (Some(early), Some(on_wheel)) => early > on_wheel,
+            (early, _) => early.is_some(),
+        };
+
+        let task = if from_early {
+            self.early.pop()?
+        } else {
+            let slot = slot?;
+            let task = self.ticks[slot].pop()?;
+
+            if self.ticks[slot].is_empty() {
+                self.occupied[0] &= !(1 << slot);
+            }
+
+            self.cursor = self.cursor.max(self.tick(task.at));
+            task
+        };
+
+        self.len -= 1;
+        Some(task)
+    }
+
+    fn peek(&self) -> Option<&ScheduledTask<'a, T>> {
+        let on_wheel = match self.first_slot() {
+            Some((0, slot)) => self.ticks[slot].peek(),
+            Some((level, slot)) => self.levels[level - 1][slot].iter().max(),
+            None => None,
+        };
+
+        match (self.early.peek(), on_wheel) {
+            (Some(early), Some(on_wheel)) => Some(early.max(on_wheel)),
+            (early, on_wheel) => early.or(on_wheel),
+        }
+    }
+
+    fn remove(&mut self, id: TaskId) -> Option<ScheduledTask<'a, T>> {
+        let task = if let Some(task) = remove_from_heap(&mut self.early, id) {
+            task
+        } else if let Some(slot) = self
+            .ticks
+            .iter()
+            .position(|tick| tick.iter().any(|task| task.id == id))
+        {
+            let task = remove_from_heap(&mut self.ticks[slot], id)?;
+
+            if self.ticks[slot].is_empty() {
+                self.occupied[0] &= !(1 << slot);
+            }
+
+            task
+        } else {
+            let (level, slot, index) =
+                self.levels.iter().enumerate().find_map(|(level, slots)| {
+                    slots.iter().enumerate().find_map(|(slot, tasks)| {
+                        let index = tasks.iter().position(|task| task.id == id)?;
+                        Some((level + 1, slot, index))
+                    })
+                })?;
+
+            let tasks = &mut self.levels[level - 1][slot];
+            let task = tasks.swap_remove(index);
+
+            if tasks.is_empty() {
+                self.occupied[level] &= !(1 << slot);
+            }
+
+            task
+        };
+
+        self.len -= 1;
+        Some(task)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &ScheduledTask<'a, T>> + '_ {
+        self.early
+            .iter()
+            .chain(self.ticks.iter().flatten())
+            .chain(self.levels.iter().flatten().flatten())
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.early.shrink_to_fit();
+        self.ticks.iter_mut().for_each(BinaryHeap::shrink_to_fit);
+        self.levels
+            .iter_mut()
+            .flatten()
+            .for_each(Vec::shrink_to_fit);
+    }
+
+    fn drain(&mut self) -> Vec<ScheduledTask<'a, T>> {
+        let mut tasks = Vec::with_capacity(self.len);
+        tasks.extend(self.early.drain());
+        tasks.extend(self.ticks.iter_mut().flat_map(BinaryHeap::drain));
+        tasks.extend(
+            self.levels
+                .iter_mut()
+                .flatten()
+                .flat_map(|slot| slot.drain(..)),
+        );
+
+        self.occupied = [0; LEVELS];
+        self.len = 0;
+        tasks
+    }
+
+    fn tick(&self, at: Stbi) -> u64 {
+        let ticks = at.since(self.origin).as_nanos() / self.resolution;
+        u64::try_from(ticks).unwrap_or(u64::MAX)
+    }
+
+    /// The level and slot of `tick` relative to the cursor.
+    fn position(&self, tick: u64) -> (usize, usize) {
+        let level = match tick ^ self.cursor {
+            0 => 0,
+            differing => ((u64::BITS - 1 - differing.leading_zeros()) / SLOT_BITS) as usize,
+        };
+
+        (level, Self::slot(tick, level))
+    }
+
+    fn slot(tick: u64, level: usize) -> usize {
+        ((tick >> (level as u32 * SLOT_BITS)) as usize) & (SLOTS - 1)
+    }
+
+    /// The first occupied slot of the lowest occupied level.
+    fn first_slot(&self) -> Option<(usize, usize)> {
+        self.occupied
+            .iter()
+            .enumerate()
+            .find(|(_, occupied)| **occupied != 0)
+            .map(|(level, occupied)| (level, occupied.trailing_zeros() as usize))
+    }
+
+    /// Advances the cursor to the start of a slot and distributes its tasks
+    /// onto the lower levels.
+    fn cascade(&mut self, level: usize, slot: usize) {
+        let tasks = std::mem::take(&mut self.levels[level - 1][slot]);
+        self.occupied[level] &= !(1 << slot);
+
+        let shift = level as u32 * SLOT_BITS;
+        let block = self
+            .cursor
+            .checked_shr(shift + SLOT_BITS)
+            .and_then(|block| block.checked_shl(shift + SLOT_BITS))
+            .unwrap_or(0);
+        let start = block | (slot as u64) << shift;
+        self.cursor = self.cursor.max(start);
+
+        self.len -= tasks.len();
+
+        for task in tasks {
+            self.push(task);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng::Rng, Schedule, TaskId};
+
+    fn task(at: Stbi, seq: u64) -> ScheduledTask<'static> {
+        ScheduledTask {
+            id: TaskId(seq),
+            at,
+            wall: None,
+            seq,
+            task: Schedule::Once(None).with(|| {}),
+            rng: Rng::new(seq),
+            retry: None,
+            deferred: None,
+        }
+    }
+
+    #[test]
+    fn wheel_pops_in_heap_order() {
+        let origin = Stbi::now();
+        let mut heap = Queue::default();
+        let mut wheel = Queue::default().into_wheel(origin, Duration::from_millis(1));
+        let mut rng = Rng::new(7);
+
+        let mut offsets: Vec<_> = (0..2_000)
+            .map(|_| Duration::from_micros(rng.next_u64() % 10_000_000))
+            .collect();
+        // Ties within and across ticks, and tasks before the origin.
+        offsets.extend([Duration::ZERO; 3]);
+        offsets.extend([Duration::from_micros(5_500); 3]);
+        offsets.push(Duration::from_secs(60 * 60 * 24 * 365));
+
+        for (seq, offset) in offsets.iter().enumerate() {
+            heap.push(task(origin + *offset, seq as u64));
+            wheel.push(task(origin + *offset, seq as u64));
+        }
+
+        for seq in (0..offsets.len() as u64).step_by(97) {
+            let expected = heap.remove(TaskId(seq)).map(|task| task.seq);
+            assert_eq!(wheel.remove(TaskId(seq)).map(|task| task.seq), expected);
+            assert!(!wheel.contains(TaskId(seq)));
+        }
+        assert_eq!(wheel.len(), heap.len());
+        assert!(wheel.contains(TaskId(1)));
+
+        let mut popped = 0;
+
+        while let Some(expected) = heap.pop() {
+            assert_eq!(wheel.peek().map(|task| task.seq), Some(expected.seq));
+            assert_eq!(wheel.pop().map(|task| task.seq), Some(expected.seq));
+            assert!(!wheel.contains(TaskId(expected.seq)));
+            popped += 1;
+
+            // Tasks added behind the cursor are still popped in order.
+            if popped % 500 == 0 {
+                let seq = 10_000 + popped;
+                heap.push(task(origin, seq));
+                wheel.push(task(origin, seq));
+            }
+        }
+
+        assert_eq!(wheel.len(), 0);
+        assert!(wheel.pop().is_none());
+    }
+}
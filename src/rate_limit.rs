@@ -0,0 +1,63 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::Stbi;
+
+/// Allows at most `max` executions within any window of `per`.
+pub(crate) struct RateLimit {
+    max: usize,
+    per: Duration,
+    /// The times of the executions within the current window, oldest first.
+    fired: VecDeque<Stbi>,
+}
+
+impl RateLimit {
+    pub(crate) fn new(max: usize, per: Duration) -> Self {
+        Self {
+            max: max.max(1),
+            per,
+            fired: VecDeque::new(),
+        }
+    }
+
+    /// Takes an execution at `now` from the budget, or returns the time at
+    /// which the budget allows the next one.
+    pub(crate) fn acquire(&mut self, now: Stbi) -> Result<(), Stbi> {
+        while let Some(&oldest) = self.fired.front() {
+            if now.since(oldest) < self.per {
+                break;
+            }
+
+            self.fired.pop_front();
+        }
+
+        match self.fired.front() {
+            Some(&oldest) if self.fired.len() >= self.max => Err(oldest + self.per),
+            _ => {
+                self.fired.push_back(now);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_frees_up_after_the_window() {
+        let start = Stbi::now();
+        let second = Duration::from_secs(1);
+        let mut limit = RateLimit::new(2, second);
+
+        assert_eq!(limit.acquire(start), Ok(()));
+        assert_eq!(limit.acquire(start + second / 2), Ok(()));
+        assert_eq!(limit.acquire(start + second / 2), Err(start + second));
+
+        assert_eq!(limit.acquire(start + second), Ok(()));
+        assert_eq!(
+            limit.acquire(start + second),
+            Err(start + second / 2 + second)
+        );
+    }
+}
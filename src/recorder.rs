@@ -0,0 +1,82 @@
+use std::time::Duration;
+
+use crate::TaskId;
+
+/// Receives the events of a scheduler, e.g. to forward them to a metrics
+/// system, see [`crate::Scheduler::with_recorder`].
+///
+/// All methods default to doing nothing. They are invoked on the thread
+/// running the scheduler, so they should return quickly.
+pub trait Recorder: Send {
+    /// A task execution started `lateness` after it was due.
+    ///
+    /// Dispatching a task to a worker counts as its execution.
+    fn record_fire(&self, id: TaskId, lateness: Duration) {
+        let _ = (id, lateness);
+    }
+
+    /// A task execution took `duration`. Executions on workers are not
+    /// timed.
+    fn record_duration(&self, id: TaskId, duration: Duration) {
+        let _ = (id, duration);
+    }
+
+    /// A task was dropped before completing its schedule, i.e. it was
+    /// cancelled, removed by [`crate::Scheduler::retain`] or dropped after it
+    /// panicked.
+    fn record_drop(&self, id: TaskId) {
+        let _ = id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::{MockClock, Schedule, Scheduler};
+
+    #[derive(Clone, Default)]
+    struct Events(Arc<Mutex<Vec<(&'static str, TaskId)>>>);
+
+    impl Recorder for Events {
+        fn record_fire(&self, id: TaskId, _: Duration) {
+            self.0.lock().unwrap().push(("fire", id));
+        }
+
+        fn record_drop(&self, id: TaskId) {
+            self.0.lock().unwrap().push(("drop", id));
+        }
+    }
+
+    #[test]
+    fn events_are_recorded() {
+        let second = Duration::from_secs(1);
+        let events = Events::default();
+        let mut scheduler =
+            Scheduler::with_clock(MockClock::new(), Vec::new()).with_recorder(events.clone());
+
+        let counted = scheduler.add(
+            Schedule::Counted {
+                interval: second,
+                count: 2,
+            }
+            .with(|| {}),
+        );
+        let every = scheduler.add(Schedule::Every(second).with(|| {}));
+
+        assert_eq!(scheduler.run_n(2).fired, 2);
+        scheduler.cancel(every);
+        scheduler.run_n(1);
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            [
+                ("fire", counted),
+                ("fire", every),
+                ("fire", counted),
+                ("drop", every)
+            ]
+        );
+    }
+}
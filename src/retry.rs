@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+/// Decides how a failed execution of a task is retried, see [`crate::Task::retry`].
+///
+/// Retries happen in between the regular executions. Once the retries are
+/// exhausted the error is passed to the error handler of the scheduler and
+/// the task continues on its schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetryPolicy {
+    /// Does not retry failed executions.
+    #[default]
+    Never,
+    /// Retries up to `retries` times, waiting `delay` before each attempt.
+    Fixed { delay: Duration, retries: u32 },
+    /// Retries up to `retries` times, doubling the delay from `initial` with
+    /// every attempt without exceeding `max`.
+    Exponential {
+        initial: Duration,
+        max: Duration,
+        retries: u32,
+    },
+}
+
+impl RetryPolicy {
+    /// The delay before the retry following `attempts` failed retries, or
+    /// `None` if the retries are exhausted.
+    pub fn delay(&self, attempts: u32) -> Option<Duration> {
+        match *self {
+            Self::Never => None,
+            Self::Fixed { delay, retries } => (attempts < retries).then_some(delay),
+            Self::Exponential {
+                initial,
+                max,
+                retries,
+            } => (attempts < retries).then(|| {
+                2u32.checked_pow(attempts)
+                    .and_then(|factor| initial.checked_mul(factor))
+                    .map_or(max, |delay| delay.min(max))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_backoff_is_capped() {
+        let policy = RetryPolicy::Exponential {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+            retries: 40,
+        };
+
+        let delays: Vec<_> = (0..5)
+            .map(|attempts| policy.delay(attempts).unwrap().as_secs())
+            .collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+        assert_eq!(policy.delay(39), Some(Duration::from_secs(5)));
+        assert_eq!(policy.delay(40), None);
+    }
+}
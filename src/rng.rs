@@ -0,0 +1,59 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// A small xorshift64* generator, good enough to spread out executions.
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // Scramble the seed (splitmix64) so similar seeds diverge quickly and
+        // the state is never zero.
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        Self(if z == 0 { 0x9E37_79B9_7F4A_7C15 } else { z })
+    }
+
+    pub(crate) fn from_entropy() -> Self {
+        Self::new(RandomState::new().build_hasher().finish())
+    }
+
+    /// A generator seeded from this one, e.g. for a single task.
+    pub(crate) fn fork(&mut self) -> Self {
+        Self::new(self.next_u64())
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        x.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A random duration in `[min, max]`, in either order of the bounds.
+    pub(crate) fn between(&mut self, min: Duration, max: Duration) -> Duration {
+        let (min, max) = (min.min(max), min.max(max));
+        let span = (max - min).as_nanos() + 1;
+
+        min + crate::duration_from_nanos(u128::from(self.next_u64()) % span)
+    }
+
+    /// Offsets `base` by a random amount in `[-jitter, +jitter]`, staying
+    /// above zero.
+    pub(crate) fn jitter(&mut self, base: Duration, jitter: Duration) -> Duration {
+        let span = jitter.as_nanos() * 2 + 1;
+        let offset = crate::duration_from_nanos(u128::from(self.next_u64()) % span);
+
+        base.saturating_add(offset)
+            .saturating_sub(jitter)
+            .max(Duration::from_nanos(1))
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use crate::{
+    Clock, EmptyBehavior, Scheduler, ShutdownToken, SystemClock, Task, TaskId,
+    SHUTDOWN_POLL_INTERVAL,
+};
+
+/// A [`Scheduler`] behind a lock, which can be cloned and used from several
+/// threads.
+///
+/// Unlike a [`crate::SchedulerHandle`] any thread may run the due tasks, and
+/// every method of the scheduler is available through
+/// [`SharedScheduler::lock`].
+pub struct SharedScheduler<'a, C = SystemClock>(Arc<Shared<'a, C>>);
+
+struct Shared<'a, C> {
+    scheduler: Mutex<Scheduler<'a, C>>,
+    /// Notified when a task is added or cancelled, waking up
+    /// [`SharedScheduler::run`].
+    wakeup: Condvar,
+}
+
+impl<'a, C> SharedScheduler<'a, C> {
+    pub fn new(scheduler: Scheduler<'a, C>) -> Self {
+        Self(Arc::new(Shared {
+            scheduler: Mutex::new(scheduler),
+            wakeup: Condvar::new(),
+        }))
+    }
+
+    /// Locks the scheduler, blocking while another thread holds it, e.g.
+    /// while it executes due tasks.
+    ///
+    /// Unlike [`SharedScheduler::add`] changes through the lock do not wake
+    /// up [`SharedScheduler::run`], they are noticed once its sleep ends;
+    /// see [`SharedScheduler::wake`].
+    pub fn lock(&self) -> MutexGuard<'_, Scheduler<'a, C>> {
+        self.0
+            .scheduler
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Wakes up [`SharedScheduler::run`] to check for due tasks.
+    pub fn wake(&self) {
+        self.0.wakeup.notify_all();
+    }
+}
+
+impl<'a, C: Clock> SharedScheduler<'a, C> {
+    /// See [`Scheduler::add`].
+    ///
+    /// Wakes up [`SharedScheduler::run`], so the task executes on time even
+    /// if it is due before the task the scheduler was sleeping for.
+    pub fn add(&self, task: Task<'a>) -> TaskId {
+        let id = self.lock().add(task);
+        self.wake();
+        id
+    }
+
+    /// See [`Scheduler::cancel`].
+    pub fn cancel(&self, id: TaskId) -> bool {
+        let cancelled = self.lock().cancel(id);
+        self.wake();
+        cancelled
+    }
+
+    /// See [`Scheduler::len`].
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// See [`Scheduler::is_empty`].
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Executes the due tasks while holding the lock, see
+    /// [`Scheduler::run_pending`].
+    pub fn run_pending(&self) -> Option<Duration> {
+        self.lock().run_pending()
+    }
+}
+
+impl<C: Clock + Clone> SharedScheduler<'_, C> {
+    /// Runs the scheduler until no tasks are left, or forever if it blocks
+    /// while empty (see [`Scheduler::with_empty_behavior`]).
+    ///
+    /// The lock is only held while due tasks execute and released while
+    /// sleeping, so other threads can add and cancel tasks in between. Sleeps
+    /// last at most one sleep slice (see [`Scheduler::with_sleep_slice`]) and
+    /// are cut short by [`SharedScheduler::add`], [`SharedScheduler::cancel`]
+    /// and [`SharedScheduler::wake`].
+    pub fn run(&self) {
+        self.run_while(None)
+    }
+
+    /// Like [`SharedScheduler::run`], but also stops once `token` is shut
+    /// down.
+    ///
+    /// The shutdown is noticed like by [`Scheduler::run_with_shutdown`],
+    /// within a fraction of a second.
+    pub fn run_with_shutdown(&self, token: ShutdownToken) {
+        self.run_while(Some(&token))
+    }
+
+    fn run_while(&self, token: Option<&ShutdownToken>) {
+        let mut scheduler = self.lock();
+        let (clock, mut slice) = (scheduler.clock.clone(), scheduler.sleep_slice);
+        let _stopped = scheduler.state.stop_on_drop();
+
+        if token.is_some() {
+            slice = slice.min(SHUTDOWN_POLL_INTERVAL);
+        }
+
+        while !token.is_some_and(ShutdownToken::is_shutdown) {
+            let diff = match scheduler.run_pending() {
+                Some(diff) => diff,
+                None if scheduler.empty_behavior == EmptyBehavior::Block => slice,
+                None => return,
+            };
+
+            if !diff.is_zero() {
+                scheduler = clock.sleep_or_wake(&self.0.wakeup, scheduler, diff.min(slice));
+            }
+        }
+    }
+}
+
+impl<C> Clone for SharedScheduler<'_, C> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<'a, C> From<Scheduler<'a, C>> for SharedScheduler<'a, C> {
+    fn from(scheduler: Scheduler<'a, C>) -> Self {
+        Self::new(scheduler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread};
+
+    use super::*;
+    use crate::Schedule;
+
+    #[test]
+    fn tasks_are_added_while_running() {
+        let (sender, receiver) = mpsc::channel();
+        let shared =
+            SharedScheduler::new(Scheduler::new().with_sleep_slice(Duration::from_millis(5)));
+
+        let keep_alive = shared.add(Schedule::Every(Duration::from_millis(5)).with(|| {}));
+        let runner = {
+            let shared = shared.clone();
+            thread::spawn(move || shared.run())
+        };
+
+        shared.add(Schedule::Once(None).with(move || sender.send(()).unwrap()));
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(shared.cancel(keep_alive));
+        runner.join().unwrap();
+        assert!(shared.is_empty());
+    }
+
+    #[test]
+    fn added_tasks_wake_up_the_runner() {
+        let (sender, receiver) = mpsc::channel();
+        let hour = Duration::from_secs(60 * 60);
+        let shared = SharedScheduler::new(Scheduler::new().with_sleep_slice(hour));
+
+        let keep_alive = shared.add(Schedule::Every(hour).with(|| {}));
+        let runner = {
+            let shared = shared.clone();
+            thread::spawn(move || shared.run())
+        };
+
+        // Give the runner time to fall asleep until the hourly task.
+        thread::sleep(Duration::from_millis(20));
+        shared.add(Schedule::Once(None).with(move || sender.send(()).unwrap()));
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        shared.cancel(keep_alive);
+        runner.join().unwrap();
+    }
+
+    #[test]
+    fn empty_schedulers_block_until_shut_down() {
+        let (sender, receiver) = mpsc::channel();
+        let token = ShutdownToken::new();
+        let shared = SharedScheduler::new(
+            Scheduler::new()
+                .with_sleep_slice(Duration::from_secs(60 * 60))
+                .with_empty_behavior(EmptyBehavior::Block),
+        );
+
+        let runner = {
+            let (shared, token) = (shared.clone(), token.clone());
+            thread::spawn(move || shared.run_with_shutdown(token))
+        };
+
+        // The runner waits for tasks instead of returning right away.
+        thread::sleep(Duration::from_millis(20));
+        assert!(!runner.is_finished());
+        shared.add(Schedule::Once(None).with(move || sender.send(()).unwrap()));
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        token.shutdown();
+        runner.join().unwrap();
+        assert!(shared.is_empty());
+    }
+}
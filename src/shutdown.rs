@@ -0,0 +1,92 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{Clock, Scheduler};
+
+/// Signals a running [`crate::Scheduler`] to stop.
+///
+/// Clones share the same flag, so a clone can be handed to another thread
+/// which then stops the scheduler.
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownToken(Arc<AtomicBool>);
+
+impl ShutdownToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn shutdown(&self) {
+        self.0.store(true, Ordering::SeqCst)
+    }
+
+    pub fn is_shutdown(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Stops a scheduler started by [`Scheduler::run_detached`].
+#[derive(Debug, Clone)]
+pub struct StopHandle(ShutdownToken);
+
+impl StopHandle {
+    /// Stops the scheduler once its currently executing task finished.
+    ///
+    /// Join the handle returned alongside to wait for the scheduler to exit.
+    pub fn stop(&self) {
+        self.0.shutdown()
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.is_shutdown()
+    }
+}
+
+impl<C: Clock + Send + 'static> Scheduler<'static, C> {
+    /// Runs the scheduler on its own thread until it is stopped or no tasks
+    /// are left, see [`Scheduler::run_with_shutdown`].
+    ///
+    /// Unlike [`Scheduler::spawn`] no tasks can be added once the scheduler
+    /// runs, which suits a fixed set of background tasks. The thread resumes
+    /// panics of tasks which are not caught by the panic handler, which
+    /// joining the handle reports.
+    pub fn run_detached(self) -> (JoinHandle<()>, StopHandle) {
+        let token = ShutdownToken::new();
+        let stop = StopHandle(token.clone());
+
+        let thread = thread::Builder::new()
+            .name(String::from("casched"))
+            .spawn(move || self.run_with_shutdown(token))
+            .expect("Failed to spawn scheduler thread");
+
+        (thread, stop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use super::*;
+    use crate::Schedule;
+
+    #[test]
+    fn detached_scheduler_stops_on_request() {
+        let (sender, receiver) = mpsc::channel();
+        let scheduler = Scheduler::with_tasks(vec![Schedule::Every(Duration::from_millis(5))
+            .with(move || {
+                let _ = sender.send(());
+            })]);
+
+        let (thread, stop) = scheduler.run_detached();
+        receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        stop.stop();
+        assert!(stop.is_stopped());
+        thread.join().unwrap();
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::mpsc::{SyncSender, TrySendError},
+    time::Duration,
+};
+
+use crate::{RetryPolicy, Schedule, Task};
+
+impl Schedule {
+    /// Creates a task sending the items produced by `produce` into a bounded
+    /// channel without dropping items while the channel is full.
+    ///
+    /// If the channel is full the item is kept and sending it is retried
+    /// every `retry_delay` instead of producing a new one, so a slow consumer
+    /// slows down the producer. Executions which are missed meanwhile follow
+    /// [`crate::Task::missed_ticks`]. Once the receiver was dropped nothing
+    /// is produced anymore, the task should then be cancelled.
+    pub fn with_sink<'a, I, F>(
+        self,
+        sender: SyncSender<I>,
+        retry_delay: Duration,
+        mut produce: F,
+    ) -> Task<'a>
+    where
+        I: 'a + Send,
+        F: 'a + Send + FnMut() -> I,
+    {
+        let mut pending = None;
+        let mut disconnected = false;
+
+        self.with_result(move || {
+            if disconnected {
+                return Ok(());
+            }
+
+            let item = pending.take().unwrap_or_else(&mut produce);
+
+            match sender.try_send(item) {
+                Ok(()) => Ok(()),
+                Err(TrySendError::Full(item)) => {
+                    pending = Some(item);
+                    Err(SinkFull)
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    disconnected = true;
+                    Ok(())
+                }
+            }
+        })
+        .retry(RetryPolicy::Fixed {
+            delay: retry_delay,
+            retries: u32::MAX,
+        })
+    }
+}
+
+/// The channel of a task created by [`Schedule::with_sink`] is full.
+#[derive(Debug)]
+struct SinkFull;
+
+impl fmt::Display for SinkFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the channel is full")
+    }
+}
+
+impl Error for SinkFull {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::{Clock, MockClock, Scheduler};
+
+    #[test]
+    fn full_channels_defer_the_producer() {
+        let second = Duration::from_secs(1);
+        let retry_delay = Duration::from_millis(100);
+        let clock = MockClock::new();
+        let (sender, receiver) = mpsc::sync_channel(1);
+
+        let mut next = 0;
+        let task = Schedule::Counted {
+            interval: second,
+            count: 3,
+        }
+        .with_sink(sender, retry_delay, move || {
+            next += 1;
+            next
+        });
+        let mut scheduler = Scheduler::with_clock(clock.clone(), vec![task]);
+
+        clock.advance(second);
+        scheduler.run_pending();
+        clock.advance(second);
+        assert_eq!(scheduler.run_pending(), Some(retry_delay));
+
+        // Still full, the item waits for the consumer.
+        clock.advance(retry_delay);
+        scheduler.run_pending();
+        assert_eq!(receiver.try_recv(), Ok(1));
+
+        clock.advance(retry_delay);
+        scheduler.run_pending();
+        assert_eq!(receiver.try_recv(), Ok(2));
+
+        scheduler.run_until(clock.now() + second * 2);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [3]);
+        assert!(scheduler.is_empty());
+    }
+}
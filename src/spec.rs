@@ -0,0 +1,352 @@
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Clock, Schedule, Scheduler, TaskId, TimeOfDay, Weekday};
+
+/// The definition of a [`Schedule`] without any closures, so that it can be
+/// persisted.
+///
+/// With the `serde` feature enabled it implements `Serialize` and
+/// `Deserialize`. [`Schedule::Checked`], [`Schedule::Dynamic`] and
+/// [`Schedule::SelfDirected`] are driven by closures and [`Schedule::OnceAt`]
+/// as well as [`Schedule::CountedOrUntil`] by an instant, so they have no
+/// spec. Neither have the schedules in a [`crate::TimeZone`] such as
+/// [`Schedule::Daily`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ScheduleSpec {
+    Once(Option<Duration>),
+    At(SystemTime),
+    Every(Duration),
+    FixedRate(Duration),
+    FixedDelay(Duration),
+    EveryAligned(Duration),
+    EveryWithOffset {
+        interval: Duration,
+        offset: Duration,
+    },
+    EveryJittered {
+        interval: Duration,
+        jitter: Duration,
+    },
+    RandomInterval {
+        min: Duration,
+        max: Duration,
+    },
+    Counted {
+        interval: Duration,
+        count: usize,
+    },
+    Cron(String),
+    DailyUtc {
+        at: TimeOfDay,
+    },
+    WeeklyUtc {
+        weekday: Weekday,
+        at: TimeOfDay,
+    },
+    Then(Box<ScheduleSpec>, Box<ScheduleSpec>),
+    Never,
+}
+
+impl ScheduleSpec {
+    /// Captures the definition of `schedule`, or `None` if it depends on a
+    /// closure or an instant.
+    pub fn from_schedule(schedule: &Schedule) -> Option<Self> {
+        Some(match schedule {
+            Schedule::Once(delay) => Self::Once(*delay),
+            Schedule::At(time) => Self::At(*time),
+            Schedule::Every(interval) => Self::Every(*interval),
+            Schedule::FixedRate(interval) => Self::FixedRate(*interval),
+            Schedule::FixedDelay(interval) => Self::FixedDelay(*interval),
+            Schedule::EveryAligned(interval) => Self::EveryAligned(*interval),
+            Schedule::EveryWithOffset { interval, offset } => Self::EveryWithOffset {
+                interval: *interval,
+                offset: *offset,
+            },
+            Schedule::EveryJittered { interval, jitter } => Self::EveryJittered {
+                interval: *interval,
+                jitter: *jitter,
+            },
+            Schedule::RandomInterval { min, max } => Self::RandomInterval {
+                min: *min,
+                max: *max,
+            },
+            Schedule::Counted { interval, count } => Self::Counted {
+                interval: *interval,
+                count: *count,
+            },
+            Schedule::Cron(expression) => Self::Cron(expression.clone()),
+            Schedule::DailyUtc { at } => Self::DailyUtc { at: *at },
+            Schedule::WeeklyUtc { weekday, at } => Self::WeeklyUtc {
+                weekday: *weekday,
+                at: *at,
+            },
+            Schedule::Then(first, second) => Self::Then(
+                Box::new(Self::from_schedule(first)?),
+                Box::new(Self::from_schedule(second)?),
+            ),
+            Schedule::Never => Self::Never,
+            Schedule::OnceAt(_)
+            | Schedule::CountedOrUntil { .. }
+            | Schedule::Checked { .. }
+            | Schedule::Dynamic { .. }
+            | Schedule::SelfDirected { .. }
+            | Schedule::CronIn { .. }
+            | Schedule::Daily { .. }
+            | Schedule::Weekly { .. } => return None,
+        })
+    }
+}
+
+impl From<ScheduleSpec> for Schedule {
+    fn from(spec: ScheduleSpec) -> Self {
+        match spec {
+            ScheduleSpec::Once(delay) => Self::Once(delay),
+            ScheduleSpec::At(time) => Self::At(time),
+            ScheduleSpec::Every(interval) => Self::Every(interval),
+            ScheduleSpec::FixedRate(interval) => Self::FixedRate(interval),
+            ScheduleSpec::FixedDelay(interval) => Self::FixedDelay(interval),
+            ScheduleSpec::EveryAligned(interval) => Self::EveryAligned(interval),
+            ScheduleSpec::EveryWithOffset { interval, offset } => {
+                Self::EveryWithOffset { interval, offset }
+            }
+            ScheduleSpec::EveryJittered { interval, jitter } => {
+                Self::EveryJittered { interval, jitter }
+            }
+            ScheduleSpec::RandomInterval { min, max } => Self::RandomInterval { min, max },
+            ScheduleSpec::Counted { interval, count } => Self::Counted { interval, count },
+            ScheduleSpec::Cron(expression) => Self::Cron(expression),
+            ScheduleSpec::DailyUtc { at } => Self::DailyUtc { at },
+            ScheduleSpec::WeeklyUtc { weekday, at } => Self::WeeklyUtc { weekday, at },
+            ScheduleSpec::Then(first, second) => {
+                Self::Then(Box::new((*first).into()), Box::new((*second).into()))
+            }
+            ScheduleSpec::Never => Self::Never,
+        }
+    }
+}
+
+/// A pending task as captured by [`Scheduler::specs`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TaskSpec {
+    /// The name of the task, which identifies it across restarts, see
+    /// [`Task::named`](crate::Task::named).
+    pub name: Option<String>,
+    /// The remaining schedule, e.g. the executions left for
+    /// [`ScheduleSpec::Counted`].
+    pub schedule: ScheduleSpec,
+    /// The delay until the next execution at the time of the capture.
+    ///
+    /// Instants can not be persisted, so the position of relative timers is
+    /// kept as a delay instead. It is ignored for [`ScheduleSpec::At`] and the
+    /// calendar schedules such as [`ScheduleSpec::Cron`], which are bound to
+    /// the wall clock, and for [`ScheduleSpec::Never`].
+    pub delay: Duration,
+}
+
+impl<'a, C: Clock> Scheduler<'a, C> {
+    /// Captures the pending tasks in the order of their next execution.
+    ///
+    /// Tasks whose schedule can not be represented by a [`ScheduleSpec`] are
+    /// left out. Ids are only valid within this scheduler, so tasks to be
+    /// restored after a restart should be named: the name is kept in the
+    /// spec and allows associating it with its function, which is passed
+    /// again to [`Scheduler::restore`].
+    pub fn specs(&self) -> Vec<(TaskId, TaskSpec)> {
+        let now = self.clock.now();
+
+        let mut specs: Vec<_> = self
+            .schedule
+            .iter()
+            .filter(|task| !self.cancelled.contains(&task.id))
+            .filter_map(|task| {
+                let schedule = ScheduleSpec::from_schedule(&task.task.schedule)?;
+                let delay = task.at.since(now);
+                let name = task.task.name().map(String::from);
+                Some((
+                    task.id,
+                    TaskSpec {
+                        name,
+                        schedule,
+                        delay,
+                    },
+                ))
+            })
+            .collect();
+
+        specs.sort_by_key(|(_, spec)| spec.delay);
+        specs
+    }
+
+    /// Schedules `f` according to a spec captured by [`Scheduler::specs`],
+    /// continuing where the captured task left off.
+    ///
+    /// The restored task gets a new id and keeps the name of the spec.
+    pub fn restore<F>(&mut self, spec: TaskSpec, f: F) -> TaskId
+    where
+        F: 'a + Send + FnMut(),
+    {
+        let id = self.next_task_id();
+        let mut task = Schedule::from(spec.schedule).with(f);
+
+        if let Some(name) = spec.name {
+            task = task.named(name);
+        }
+
+        let now = self.clock.now();
+        let system_now = self.clock.system_now();
+
+        self.insert_with(id, task, now, system_now, |schedule, rng| {
+            if schedule.is_wall_clock() || matches!(schedule, Schedule::Never) {
+                schedule.first_at(now, system_now, rng)
+            } else {
+                now + spec.delay
+            }
+        });
+
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn restored_tasks_continue_where_they_left_off() {
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let every = scheduler.add(
+            Schedule::Every(Duration::from_secs(10))
+                .with(|| {})
+                .named("every"),
+        );
+        let counted = scheduler.add(
+            Schedule::Counted {
+                interval: Duration::from_secs(3),
+                count: 2,
+            }
+            .with(|| {}),
+        );
+        scheduler.add(
+            Schedule::Checked {
+                interval: Duration::from_secs(1),
+                check: Box::new(|| true),
+            }
+            .with(|| {}),
+        );
+
+        clock.advance(Duration::from_millis(4500));
+        scheduler.run_pending();
+
+        let specs = scheduler.specs();
+        assert_eq!(
+            specs,
+            vec![
+                (
+                    counted,
+                    TaskSpec {
+                        name: None,
+                        schedule: ScheduleSpec::Counted {
+                            interval: Duration::from_secs(3),
+                            count: 1,
+                        },
+                        delay: Duration::from_millis(1500),
+                    }
+                ),
+                (
+                    every,
+                    TaskSpec {
+                        name: Some(String::from("every")),
+                        schedule: ScheduleSpec::Every(Duration::from_secs(10)),
+                        delay: Duration::from_millis(5500),
+                    }
+                ),
+            ]
+        );
+
+        let mut restored = Scheduler::with_clock(MockClock::new(), Vec::new());
+        for (_, spec) in specs {
+            restored.restore(spec, || {});
+        }
+
+        assert_eq!(restored.len(), 2);
+        assert_eq!(
+            restored.time_until_next(),
+            Some(Duration::from_millis(1500))
+        );
+
+        let restored: Vec<_> = restored.specs().into_iter().map(|(_, spec)| spec).collect();
+        let original: Vec<_> = scheduler
+            .specs()
+            .into_iter()
+            .map(|(_, spec)| spec)
+            .collect();
+        assert_eq!(restored, original);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialized_specs_are_restored_by_name() {
+        use std::{
+            collections::HashMap,
+            sync::{
+                atomic::{AtomicUsize, Ordering},
+                Arc,
+            },
+        };
+
+        let clock = MockClock::new();
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        scheduler.add(
+            Schedule::Every(Duration::from_secs(10))
+                .with(|| {})
+                .named("sync"),
+        );
+        scheduler.add(
+            Schedule::Once(Some(Duration::from_secs(3)))
+                .with(|| {})
+                .named("report"),
+        );
+
+        clock.advance(Duration::from_secs(1));
+        let specs: Vec<_> = scheduler
+            .specs()
+            .into_iter()
+            .map(|(_, spec)| spec)
+            .collect();
+        let json = serde_json::to_string(&specs).unwrap();
+
+        let runs: HashMap<_, _> = ["sync", "report"]
+            .into_iter()
+            .map(|name| (name, Arc::new(AtomicUsize::new(0))))
+            .collect();
+        let clock = MockClock::new();
+        let mut restored = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        for spec in serde_json::from_str::<Vec<TaskSpec>>(&json).unwrap() {
+            let runs = Arc::clone(&runs[spec.name.as_deref().unwrap()]);
+            restored.restore(spec, move || {
+                runs.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+
+        let restored_specs: Vec<_> = restored.specs().into_iter().map(|(_, spec)| spec).collect();
+        assert_eq!(restored_specs, specs);
+
+        clock.advance(Duration::from_secs(2));
+        restored.run_pending();
+        assert_eq!(runs["report"].load(Ordering::SeqCst), 1);
+        assert_eq!(runs["sync"].load(Ordering::SeqCst), 0);
+
+        clock.advance(Duration::from_secs(7));
+        restored.run_pending();
+        assert_eq!(runs["sync"].load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{Schedule, Task};
+
+impl Schedule {
+    /// Creates a task whose function receives mutable access to `state` on
+    /// every execution.
+    pub fn with_state<'a, S, F>(self, mut state: S, mut f: F) -> Task<'a>
+    where
+        S: 'a + Send,
+        F: 'a + Send + FnMut(&mut S),
+    {
+        self.with(move || f(&mut state))
+    }
+
+    /// Like [`Schedule::with_state`], but also returns a handle which receives
+    /// the state once the task is dropped, e.g. after the last execution of a
+    /// [`Schedule::Counted`] task.
+    pub fn with_final_state<'a, S, F>(self, state: S, mut f: F) -> (Task<'a>, FinalState<S>)
+    where
+        S: 'a + Send,
+        F: 'a + Send + FnMut(&mut S),
+    {
+        let slot = Arc::new(Mutex::new(None));
+        let mut keeper = Keeper {
+            state: Some(state),
+            slot: Arc::clone(&slot),
+        };
+
+        let task = self.with(move || {
+            if let Some(state) = &mut keeper.state {
+                f(state)
+            }
+        });
+
+        (task, FinalState(slot))
+    }
+}
+
+/// Receives the state of a task created by [`Schedule::with_final_state`].
+#[derive(Debug)]
+pub struct FinalState<S>(Arc<Mutex<Option<S>>>);
+
+impl<S> FinalState<S> {
+    /// Takes the state, or returns `None` while the task still exists.
+    pub fn take(&self) -> Option<S> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+}
+
+/// Hands the state over to its [`FinalState`] when the task function is
+/// dropped.
+struct Keeper<S> {
+    state: Option<S>,
+    slot: Arc<Mutex<Option<S>>>,
+}
+
+impl<S> Drop for Keeper<S> {
+    fn drop(&mut self) {
+        *self.slot.lock().unwrap_or_else(PoisonError::into_inner) = self.state.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{MockClock, Scheduler};
+
+    #[test]
+    fn final_state_is_returned_after_last_execution() {
+        let schedule = Schedule::Counted {
+            interval: Duration::from_secs(1),
+            count: 3,
+        };
+        let (task, state) = schedule.with_final_state(Vec::new(), |runs| runs.push(runs.len()));
+
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), vec![task]);
+        scheduler.run_pending();
+        assert_eq!(state.take(), None);
+
+        scheduler.run();
+        assert_eq!(state.take(), Some(vec![0, 1, 2]));
+    }
+}
@@ -0,0 +1,53 @@
+use std::{sync::Arc, time::Duration};
+
+/// Timing statistics of a task, see [`crate::Scheduler::stats`].
+///
+/// Lateness is the time between when an execution was due and when it
+/// actually started.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TaskStats {
+    /// The name of the task, see [`crate::Task::named`].
+    pub name: Option<Arc<str>>,
+    /// The number of executions so far.
+    pub runs: u64,
+    pub min_lateness: Duration,
+    pub max_lateness: Duration,
+    /// How long the last execution took, unless it ran on a worker thread.
+    pub last_duration: Option<Duration>,
+    /// The number of executions which took longer than the interval of the
+    /// task, see [`crate::Scheduler::on_overrun`].
+    pub overruns: u64,
+    /// The number of executions which started later than the grace period
+    /// of the scheduler, see [`crate::Scheduler::with_grace_period`].
+    pub overdue: u64,
+    total_lateness: Duration,
+}
+
+impl TaskStats {
+    pub(crate) fn named(name: Option<Arc<str>>) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    pub fn mean_lateness(&self) -> Duration {
+        match u32::try_from(self.runs) {
+            Ok(0) => Duration::ZERO,
+            Ok(runs) => self.total_lateness / runs,
+            Err(_) => self.max_lateness,
+        }
+    }
+
+    pub(crate) fn record(&mut self, lateness: Duration, duration: Option<Duration>) {
+        if self.runs == 0 {
+            self.min_lateness = lateness;
+        }
+
+        self.runs += 1;
+        self.min_lateness = self.min_lateness.min(lateness);
+        self.max_lateness = self.max_lateness.max(lateness);
+        self.total_lateness = self.total_lateness.saturating_add(lateness);
+        self.last_duration = duration;
+    }
+}
@@ -0,0 +1,91 @@
+use std::sync::{
+    atomic::{AtomicU8, Ordering},
+    Arc,
+};
+
+/// What a [`crate::Scheduler`] is currently doing, see [`StateHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchedulerState {
+    /// Sleeping until the next task is due, or waiting for the next call of
+    /// [`crate::Scheduler::run_pending`] when driven manually.
+    Idle,
+    /// Executing due tasks.
+    Firing,
+    /// Not running, either because it was not started yet or because a run
+    /// loop like [`crate::Scheduler::run`] returned.
+    Stopped,
+}
+
+impl SchedulerState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Idle,
+            1 => Self::Firing,
+            _ => Self::Stopped,
+        }
+    }
+}
+
+/// Reads the [`SchedulerState`] of a scheduler from another thread, see
+/// [`crate::Scheduler::state_handle`].
+///
+/// Clones share the same state.
+#[derive(Debug, Clone)]
+pub struct StateHandle(Arc<AtomicU8>);
+
+impl StateHandle {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(AtomicU8::new(SchedulerState::Stopped as u8)))
+    }
+
+    pub fn get(&self) -> SchedulerState {
+        SchedulerState::from_u8(self.0.load(Ordering::SeqCst))
+    }
+
+    pub(crate) fn set(&self, state: SchedulerState) {
+        self.0.store(state as u8, Ordering::SeqCst)
+    }
+
+    /// Returns a guard which sets the state to [`SchedulerState::Stopped`]
+    /// when dropped, even if a task panic unwinds the run loop.
+    pub(crate) fn stop_on_drop(&self) -> StopOnDrop {
+        StopOnDrop(self.clone())
+    }
+}
+
+pub(crate) struct StopOnDrop(StateHandle);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.set(SchedulerState::Stopped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Mutex, time::Duration};
+
+    use super::*;
+    use crate::{MockClock, Schedule, Scheduler};
+
+    #[test]
+    fn state_follows_the_run_loop() {
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let mut scheduler = Scheduler::with_clock(MockClock::new(), Vec::new());
+        let state = scheduler.state_handle();
+
+        assert_eq!(state.get(), SchedulerState::Stopped);
+
+        scheduler.add(Schedule::Every(Duration::from_secs(1)).with({
+            let (state, observed) = (state.clone(), Arc::clone(&observed));
+            move || observed.lock().unwrap().push(state.get())
+        }));
+
+        scheduler.run_pending();
+        assert_eq!(state.get(), SchedulerState::Idle);
+
+        scheduler.run_n(2);
+        assert_eq!(state.get(), SchedulerState::Stopped);
+        assert_eq!(*observed.lock().unwrap(), [SchedulerState::Firing; 2]);
+    }
+}
@@ -0,0 +1,154 @@
+use std::sync::PoisonError;
+
+use crate::{Clock, Job, Schedule, Scheduler, SystemClock, Task, TaskError};
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Concurrent {}
+    impl Sealed for super::Local {}
+}
+
+/// Decides whether the task functions of a [`Scheduler`] must be `Send`.
+///
+/// The trait is sealed and implemented by [`Concurrent`], the default, and
+/// [`Local`] only.
+pub trait Threading: sealed::Sealed + 'static {
+    /// The function of a task created by [`crate::Schedule::with`].
+    type Function<'a>: ?Sized + FnMut() + 'a;
+    /// The function of a task created by [`crate::Schedule::with_result`].
+    type Fallible<'a>: ?Sized + FnMut() -> Result<(), TaskError> + 'a;
+    /// The function of a task created by [`crate::Schedule::with_once`].
+    type Once<'a>: ?Sized + FnOnce() + 'a;
+}
+
+/// Task functions are `Send`, so the scheduler can be moved onto its own
+/// thread ([`Scheduler::spawn`]) and execute tasks on workers
+/// ([`Scheduler::with_workers`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Concurrent {}
+
+impl Threading for Concurrent {
+    type Function<'a> = dyn FnMut() + Send + 'a;
+    type Fallible<'a> = dyn FnMut() -> Result<(), TaskError> + Send + 'a;
+    type Once<'a> = dyn FnOnce() + Send + 'a;
+}
+
+/// Task functions need not be `Send`, e.g. to capture an `Rc`, see
+/// [`LocalScheduler`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Local {}
+
+impl Threading for Local {
+    type Function<'a> = dyn FnMut() + 'a;
+    type Fallible<'a> = dyn FnMut() -> Result<(), TaskError> + 'a;
+    type Once<'a> = dyn FnOnce() + 'a;
+}
+
+/// A scheduler which stays on the thread it was created on, so that its
+/// task functions need not be `Send`.
+///
+/// It is created by [`Scheduler::new_local`] or [`Scheduler::with_local_clock`]
+/// and holds tasks created by [`crate::Schedule::with_local`]. Tasks with
+/// `Send` functions, e.g. those created by [`crate::Schedule::with`] or a
+/// [`crate::TaskBuilder`], are converted with [`Into`]. The handlers and
+/// callbacks of the scheduler, the conditions of [`Task::when`] as well as the
+/// closures of [`crate::Schedule::Checked`] and [`crate::Schedule::Dynamic`]
+/// still have to be `Send`. Spawning and workers are not available.
+pub type LocalScheduler<'a, C = SystemClock> = Scheduler<'a, C, Local>;
+
+/// A task of a [`LocalScheduler`].
+pub type LocalTask<'a> = Task<'a, Local>;
+
+impl Schedule {
+    /// Like [`Schedule::with`], but creates a task of a [`LocalScheduler`],
+    /// whose function need not be `Send`.
+    pub fn with_local<'a, F>(self, f: F) -> LocalTask<'a>
+    where
+        F: 'a + FnMut(),
+    {
+        let f: Box<dyn FnMut() + 'a> = Box::new(f);
+        Task::new(self, Job::Repeating(f))
+    }
+}
+
+impl<'a> Scheduler<'a, SystemClock, Local> {
+    /// Like [`Scheduler::new`], but creates a [`LocalScheduler`].
+    pub fn new_local() -> Self {
+        Self::with_local_clock(SystemClock, Vec::new())
+    }
+}
+
+impl<'a, C: Clock> Scheduler<'a, C, Local> {
+    /// Like [`Scheduler::with_clock`], but creates a [`LocalScheduler`].
+    pub fn with_local_clock(clock: C, tasks: Vec<LocalTask<'a>>) -> Self {
+        let now = clock.now();
+        Self::build(clock, tasks, now)
+    }
+}
+
+impl<'a> From<Job<'a>> for Job<'a, Local> {
+    fn from(job: Job<'a>) -> Self {
+        match job {
+            Job::Repeating(f) => Self::Repeating(f),
+            Job::Fallible(f) => Self::Fallible(f),
+            Job::Once(f) => Self::Once(f.map(|f| -> Box<dyn FnOnce() + 'a> { f })),
+            Job::Shared(job) => Self::Fallible(Box::new(move || {
+                job.lock().unwrap_or_else(PoisonError::into_inner).call()
+            })),
+            Job::Directed { f, returned } => Self::Directed { f, returned },
+            #[cfg(feature = "tokio")]
+            Job::Async(f) => Self::Async(f),
+        }
+    }
+}
+
+impl<'a> From<Task<'a>> for LocalTask<'a> {
+    /// Converts a task with a `Send` function for a [`LocalScheduler`].
+    fn from(task: Task<'a>) -> Self {
+        Task {
+            schedule: task.schedule,
+            f: task.f.into(),
+            priority: task.priority,
+            retry: task.retry,
+            missed_ticks: task.missed_ticks,
+            immediate: task.immediate,
+            name: task.name,
+            group: task.group,
+            timeout: task.timeout,
+            overlap: task.overlap,
+            condition: task.condition,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc, time::Duration};
+
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn local_tasks_need_not_be_send() {
+        let second = Duration::from_secs(1);
+        let runs = Rc::new(Cell::new(0));
+        let clock = MockClock::new();
+
+        let mut scheduler = Scheduler::with_local_clock(
+            clock.clone(),
+            vec![Schedule::Every(second).with_local({
+                let runs = Rc::clone(&runs);
+                move || runs.set(runs.get() + 1)
+            })],
+        );
+        scheduler.add(Schedule::Once(None).with(|| {}).into());
+
+        assert_eq!(scheduler.run_pending(), Some(second));
+        clock.advance(second);
+        scheduler.run_pending();
+
+        assert_eq!(runs.get(), 1);
+        assert_eq!(scheduler.len(), 1);
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(feature = "tokio")]
+use std::{future::Future, pin::Pin};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, MutexGuard,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{Clock, Stbi};
+
+/// A virtual clock counting whole ticks of a fixed length, e.g. the steps of
+/// a simulation, which only advances when told to.
+///
+/// Like [`crate::MockClock`], but its time is an integer number of ticks.
+/// Schedules whose intervals are whole ticks, see [`TickClock::ticks`], keep
+/// every execution exactly on a tick, as rescheduling only adds integer
+/// durations. Schedulers on this clock raise intervals to one tick instead of
+/// [`crate::DEFAULT_MIN_INTERVAL`], see [`Clock::min_interval`]. Sleeping
+/// advances by whole ticks, rounding up, so a sleeping scheduler never stops
+/// short of the task it waits for. The wall-clock advances along. Clones
+/// share the same timeline.
+///
+/// There is no separate scheduler keyed by tick counts, tasks stay keyed by
+/// [`Stbi`]. As durations are whole nanoseconds and the time of this clock is
+/// its start plus a whole number of ticks, rescheduling by whole ticks is
+/// exact integer addition and does not drift, however long a simulation
+/// runs. [`TickClock::tick_of`] converts the times back into ticks.
+#[derive(Debug, Clone)]
+pub struct TickClock {
+    start: Instant,
+    system_start: SystemTime,
+    tick: Duration,
+    elapsed_ticks: Arc<AtomicU64>,
+}
+
+impl TickClock {
+    /// Creates a clock whose ticks last `tick`, panicking if it is zero.
+    pub fn new(tick: Duration) -> Self {
+        assert!(!tick.is_zero(), "ticks must not be zero");
+
+        Self {
+            start: Instant::now(),
+            system_start: SystemTime::now(),
+            tick,
+            elapsed_ticks: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// The length of a tick.
+    pub fn tick(&self) -> Duration {
+        self.tick
+    }
+
+    pub fn advance(&self, ticks: u64) {
+        self.elapsed_ticks.fetch_add(ticks, Ordering::SeqCst);
+    }
+
+    /// The number of ticks passed since this clock was created.
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed_ticks.load(Ordering::SeqCst)
+    }
+
+    /// The duration of `ticks`, e.g. as the interval of a schedule.
+    pub fn ticks(&self, ticks: u64) -> Duration {
+        crate::duration_from_nanos(self.tick.as_nanos() * u128::from(ticks))
+    }
+
+    /// The tick `at` lies on, rounded down, or zero if it lies before this
+    /// clock was created.
+    pub fn tick_of(&self, at: Stbi) -> u64 {
+        let since = at.since(Stbi(self.start)).as_nanos() / self.tick.as_nanos();
+        u64::try_from(since).unwrap_or(u64::MAX)
+    }
+
+    /// Advances by `duration`, rounded up to whole ticks.
+    fn advance_by(&self, duration: Duration) {
+        let ticks = duration.as_nanos().div_ceil(self.tick.as_nanos());
+        self.advance(u64::try_from(ticks).unwrap_or(u64::MAX));
+    }
+}
+
+impl Clock for TickClock {
+    fn now(&self) -> Stbi {
+        Stbi(self.start) + self.ticks(self.elapsed())
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance_by(duration)
+    }
+
+    fn spin(&self, duration: Duration) {
+        self.advance_by(duration)
+    }
+
+    fn sleep_or_wake<'a, T>(
+        &self,
+        _: &Condvar,
+        guard: MutexGuard<'a, T>,
+        duration: Duration,
+    ) -> MutexGuard<'a, T> {
+        self.advance_by(duration);
+        guard
+    }
+
+    #[cfg(feature = "tokio")]
+    fn sleep_async(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let clock = self.clone();
+
+        Box::pin(async move {
+            clock.advance_by(duration);
+            tokio::task::yield_now().await;
+        })
+    }
+
+    fn min_interval(&self) -> Duration {
+        self.tick
+    }
+
+    fn system_now(&self) -> SystemTime {
+        self.system_start + self.ticks(self.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::{Schedule, Scheduler};
+
+    #[test]
+    fn executions_stay_on_whole_ticks() {
+        let clock = TickClock::new(Duration::from_nanos(7));
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        let id = scheduler.add(Schedule::Every(clock.ticks(3)).with({
+            let (clock, fired) = (clock.clone(), Arc::clone(&fired));
+            move || fired.lock().unwrap().push(clock.elapsed())
+        }));
+
+        scheduler.run_n(1000);
+
+        let expected: Vec<_> = (1..=1000).map(|n| n * 3).collect();
+        assert_eq!(*fired.lock().unwrap(), expected);
+        assert_eq!(
+            scheduler.next_fire(id).map(|at| clock.tick_of(at)),
+            Some(3003)
+        );
+
+        // Sleeping rounds up to the next whole tick.
+        clock.sleep(Duration::from_nanos(8));
+        assert_eq!(clock.elapsed(), 3002);
+    }
+
+    #[test]
+    fn long_runs_do_not_drift() {
+        // A third of a millisecond, which no whole number of milliseconds or
+        // microseconds matches.
+        let clock = TickClock::new(Duration::from_nanos(333_333));
+        let start = clock.now();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+        let id = scheduler.add(Schedule::Every(clock.ticks(7)).with({
+            let (clock, fired) = (clock.clone(), Arc::clone(&fired));
+            move || fired.lock().unwrap().push(clock.now())
+        }));
+
+        scheduler.run_until(start + clock.ticks(1_000_000));
+
+        let fired = fired.lock().unwrap();
+        assert_eq!(fired.len(), 1_000_000 / 7);
+        assert!(fired
+            .iter()
+            .zip(1..)
+            .all(|(&at, n)| at == start + clock.ticks(n * 7)));
+        assert_eq!(
+            scheduler.next_fire(id),
+            Some(start + clock.ticks((1_000_000 / 7 + 1) * 7))
+        );
+    }
+
+    #[test]
+    fn sub_millisecond_intervals_are_kept() {
+        let clock = TickClock::new(Duration::from_micros(250));
+        let mut scheduler = Scheduler::with_clock(clock.clone(), Vec::new());
+
+        let every = scheduler.add(Schedule::Every(clock.ticks(3)).with(|| {}));
+        // Intervals below one tick are raised to a tick.
+        let zero = scheduler.add(Schedule::Every(Duration::ZERO).with(|| {}));
+
+        scheduler.run_until(clock.now() + clock.ticks(12));
+
+        assert_eq!(scheduler.stats()[&every].runs, 4);
+        assert_eq!(scheduler.stats()[&zero].runs, 12);
+        assert_eq!(
+            scheduler.next_fire(every).map(|at| clock.tick_of(at)),
+            Some(15)
+        );
+    }
+}
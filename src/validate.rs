@@ -0,0 +1,167 @@
+use std::{error::Error, fmt, time::Duration};
+
+use crate::{cron::Cron, CronError, Schedule, ScheduleKind, Task};
+
+/// A schedule which can not run as intended, see [`Schedule::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleError {
+    /// A recurring schedule of the given kind has a zero interval. Such tasks
+    /// would run back-to-back if the interval was not raised to the minimum
+    /// interval of the scheduler.
+    ZeroInterval(ScheduleKind),
+    /// A [`Schedule::Counted`] or [`Schedule::CountedOrUntil`] with a count
+    /// of zero, which never runs.
+    ZeroCount,
+    /// A [`Schedule::EveryWithOffset`] whose offset is not shorter than its
+    /// interval.
+    OffsetExceedsInterval {
+        offset: Duration,
+        interval: Duration,
+    },
+    /// A [`Schedule::RandomInterval`] whose `min` exceeds its `max`.
+    InvertedRange { min: Duration, max: Duration },
+    /// A [`Schedule::Cron`] with an invalid expression.
+    Cron(CronError),
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroInterval(kind) => write!(f, "`{kind}` has a zero interval"),
+            Self::ZeroCount => write!(f, "the schedule has a count of zero"),
+            Self::OffsetExceedsInterval { offset, interval } => write!(
+                f,
+                "`EveryWithOffset` has an offset of {offset:?}, not below its interval of {interval:?}"
+            ),
+            Self::InvertedRange { min, max } => {
+                write!(
+                    f,
+                    "`RandomInterval` has a min of {min:?} above its max of {max:?}"
+                )
+            }
+            Self::Cron(error) => write!(f, "invalid cron expression: {error}"),
+        }
+    }
+}
+
+impl Error for ScheduleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Cron(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Schedule {
+    /// Checks the schedule for parameters which make it misbehave, e.g. a
+    /// zero interval or a count of zero.
+    ///
+    /// Both stages of a [`Schedule::Then`] are checked.
+    pub fn validate(&self) -> Result<(), ScheduleError> {
+        match self {
+            Self::Every(interval)
+            | Self::FixedRate(interval)
+            | Self::FixedDelay(interval)
+            | Self::EveryAligned(interval)
+            | Self::EveryWithOffset { interval, .. }
+            | Self::EveryJittered { interval, .. }
+            | Self::Checked { interval, .. }
+                if interval.is_zero() =>
+            {
+                Err(ScheduleError::ZeroInterval(self.kind()))
+            }
+            Self::EveryWithOffset { interval, offset } if offset >= interval => {
+                Err(ScheduleError::OffsetExceedsInterval {
+                    offset: *offset,
+                    interval: *interval,
+                })
+            }
+            Self::Counted { count: 0, .. } | Self::CountedOrUntil { count: 0, .. } => {
+                Err(ScheduleError::ZeroCount)
+            }
+            Self::Counted { interval, .. } | Self::CountedOrUntil { interval, .. }
+                if interval.is_zero() =>
+            {
+                Err(ScheduleError::ZeroInterval(self.kind()))
+            }
+            Self::RandomInterval { min, max } if min > max => Err(ScheduleError::InvertedRange {
+                min: *min,
+                max: *max,
+            }),
+            Self::Cron(expression) | Self::CronIn { expression, .. } => Cron::parse(expression)
+                .map(|_| ())
+                .map_err(ScheduleError::Cron),
+            Self::Then(first, second) => {
+                first.validate()?;
+                second.validate()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Like [`Schedule::with`], but validates the schedule first, see
+    /// [`Schedule::validate`].
+    pub fn try_with<'a, F>(self, f: F) -> Result<Task<'a>, ScheduleError>
+    where
+        F: 'a + Send + FnMut(),
+    {
+        self.validate()?;
+        Ok(self.with(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_schedules_are_rejected() {
+        let second = Duration::from_secs(1);
+
+        assert_eq!(
+            Schedule::Every(Duration::ZERO).validate(),
+            Err(ScheduleError::ZeroInterval(ScheduleKind::Every))
+        );
+        assert_eq!(
+            Schedule::Counted {
+                interval: second,
+                count: 0
+            }
+            .validate(),
+            Err(ScheduleError::ZeroCount)
+        );
+        assert_eq!(
+            Schedule::RandomInterval {
+                min: second * 2,
+                max: second
+            }
+            .validate(),
+            Err(ScheduleError::InvertedRange {
+                min: second * 2,
+                max: second
+            })
+        );
+        assert!(matches!(
+            Schedule::Once(None)
+                .then(Schedule::Cron("* *".into()))
+                .validate(),
+            Err(ScheduleError::Cron(CronError::FieldCount(2)))
+        ));
+
+        assert_eq!(
+            Schedule::EveryWithOffset {
+                interval: second,
+                offset: second
+            }
+            .validate(),
+            Err(ScheduleError::OffsetExceedsInterval {
+                offset: second,
+                interval: second
+            })
+        );
+
+        assert!(Schedule::Every(second).try_with(|| {}).is_ok());
+        assert!(Schedule::Once(None).validate().is_ok());
+    }
+}